@@ -0,0 +1,79 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Duration, Local, NaiveDateTime};
+use ratatui::widgets::ListItem;
+
+use crate::workspace::Workspace;
+
+const TIMESTAMP: &str = "%Y-%m-%d_%H-%M-%S";
+
+// Copy the workspace's current file into the backups, then drop all but the newest `count`.
+// Nothing is copied while the newest backup is less than `interval` minutes old
+pub fn create(workspace: &Workspace, count: usize, interval: i64) -> anyhow::Result<()> {
+    if count == 0 || !workspace.file.exists() {
+        return Ok(());
+    }
+
+    let now = Local::now().naive_local();
+    if list(workspace)?
+        .first()
+        .and_then(|newest| taken(workspace, newest))
+        .is_some_and(|taken| now - taken < Duration::minutes(interval))
+    {
+        return Ok(());
+    }
+
+    let name = format!(
+        "{}-{}.json",
+        workspace.stem(),
+        Local::now().format(TIMESTAMP)
+    );
+    fs::copy(&workspace.file, workspace.backups_dir()?.join(name))?;
+
+    for old in list(workspace)?.into_iter().skip(count) {
+        fs::remove_file(old)?;
+    }
+
+    Ok(())
+}
+
+// When the backup was made, read from its name
+fn taken(workspace: &Workspace, path: &Path) -> Option<NaiveDateTime> {
+    let name = path.file_name()?.to_string_lossy();
+    let time = name
+        .strip_prefix(&format!("{}-", workspace.stem()))?
+        .strip_suffix(".json")?;
+    NaiveDateTime::parse_from_str(time, TIMESTAMP).ok()
+}
+
+// Every backup of the workspace, newest first
+pub fn list(workspace: &Workspace) -> anyhow::Result<Vec<PathBuf>> {
+    let mut backups = fs::read_dir(workspace.backups_dir()?)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        // Only count files named exactly like the ones made above
+        .filter(|path| taken(workspace, path).is_some())
+        .collect::<Vec<_>>();
+
+    // The timestamps in the names sort the same way as the times themselves
+    backups.sort();
+    backups.reverse();
+
+    Ok(backups)
+}
+
+pub fn picker_items(workspace: &Workspace) -> Vec<ListItem<'static>> {
+    list(workspace)
+        .unwrap_or_default()
+        .iter()
+        .map(|path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            ListItem::new(name)
+        })
+        .collect()
+}
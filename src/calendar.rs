@@ -0,0 +1,117 @@
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use crossterm::event::KeyCode;
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{task::Folder, Chunks};
+
+pub struct Calendar {
+    pub focused: NaiveDate,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self {
+            focused: Local::now().date_naive(),
+        }
+    }
+}
+
+impl Calendar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) {
+        let focused = match key {
+            KeyCode::Left => self.focused.checked_sub_days(Days::new(1)),
+            KeyCode::Right => self.focused.checked_add_days(Days::new(1)),
+            KeyCode::Up => self.focused.checked_sub_days(Days::new(7)),
+            KeyCode::Down => self.focused.checked_add_days(Days::new(7)),
+            KeyCode::PageUp => self.focused.checked_sub_months(Months::new(1)),
+            KeyCode::PageDown => self.focused.checked_add_months(Months::new(1)),
+            KeyCode::Char('t') => Some(Local::now().date_naive()),
+            _ => None,
+        };
+
+        if let Some(focused) = focused {
+            self.focused = focused;
+        }
+    }
+
+    pub fn render<B: Backend>(&self, frame: &mut Frame<B>, chunks: &Chunks, folder: &Folder) {
+        let tasks = folder.all_tasks();
+        let today = Local::now().date_naive();
+
+        // Find the monday on or before the first of the month, so the grid starts on a full week
+        let first = self.focused.with_day(1).expect("Day 1 should exist");
+        let mut day = first - Days::new(first.weekday().num_days_from_monday() as u64);
+
+        let mut rows = vec![];
+        while day.month() == first.month() || day < first {
+            let mut cells = vec![];
+            for _ in 0..7 {
+                let count = tasks
+                    .iter()
+                    .filter(|found| found.task.due == Some(day))
+                    .count();
+
+                let text = match count {
+                    0 => format!("{:>2}", day.day()),
+                    _ => format!("{:>2} ({})", day.day(), count),
+                };
+
+                let mut style = if day.month() != first.month() {
+                    Style::default().fg(Color::DarkGray)
+                } else if count > 0 {
+                    Style::default().fg(Color::LightGreen)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+
+                if day == today {
+                    style = style.fg(Color::LightYellow).add_modifier(Modifier::BOLD);
+                }
+
+                if day == self.focused {
+                    style = style.bg(Color::DarkGray);
+                }
+
+                cells.push(Cell::from(text).style(style));
+                day = day + Days::new(1);
+            }
+            rows.push(Row::new(cells).height(2));
+        }
+
+        let widths = [Constraint::Ratio(1, 7); 7];
+        let grid = Table::new(rows)
+            .header(
+                Row::new(["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"])
+                    .style(Style::default().fg(Color::LightCyan))
+                    .bottom_margin(1),
+            )
+            .widths(&widths)
+            .block(
+                Block::default()
+                    .title(self.focused.format("%B %Y").to_string())
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Plain),
+            );
+
+        frame.render_widget(grid, chunks.left_menu());
+
+        let due = tasks
+            .iter()
+            .filter(|found| found.task.due == Some(self.focused))
+            .map(|found| found.to_list_item())
+            .collect::<Vec<_>>();
+
+        let list = List::new(due).block(
+            Block::default()
+                .title(self.focused.format("Due %A, %B %-d").to_string())
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double),
+        );
+
+        frame.render_widget(list, chunks.right_menu());
+    }
+}
@@ -0,0 +1,41 @@
+use ratatui::{prelude::*, widgets::*};
+
+use crate::task::Task;
+
+// Draw the task's checklist, highlighting the selected item while it is being edited
+pub fn render<B: Backend>(frame: &mut Frame<B>, area: Rect, task: &Task, selected: Option<usize>) {
+    let list = task
+        .checklist
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let (mark, style) = match item.done {
+                true => ("[x]", Style::default().fg(Color::DarkGray)),
+                false => ("[ ]", Style::default()),
+            };
+
+            match Some(i) == selected {
+                true => ListItem::new(format!("{} {}", mark, item.text))
+                    .style(style.bg(Color::DarkGray).fg(Color::White)),
+                false => ListItem::new(format!("{} {}", mark, item.text)).style(style),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut title = match task.progress() {
+        Some((done, total)) => format!("Checklist {}/{}", done, total),
+        None => "Checklist".to_string(),
+    };
+    if selected.is_some() {
+        title.push_str("  <space> CHECK  <a> ADD  <e> EDIT  <d> DELETE  <esc> BACK");
+    }
+
+    let list = List::new(list).block(
+        Block::new()
+            .title(title)
+            .borders(border!(TOP))
+            .border_type(BorderType::Plain),
+    );
+
+    frame.render_widget(list, area);
+}
@@ -0,0 +1,20 @@
+use ratatui::widgets::ListItem;
+
+use crate::task::{Folder, Id, Item};
+
+// What a paste puts into the current folder
+pub enum Clipboard {
+    // Moves the item, wherever it is by then
+    Cut(Id),
+    // Adds a copy of the item as it was when copied, with new ids every time
+    Copy(Box<Item>),
+}
+
+// Every folder of the tree to pick where to move the selected item to
+pub fn picker_items(folder: &Folder) -> Vec<ListItem<'static>> {
+    folder
+        .all_folders()
+        .into_iter()
+        .map(|(_, path)| ListItem::new(path))
+        .collect()
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    task::{read_existing, write_atomic},
+    workspace::data_dir,
+};
+
+// User settings, read from config.json in the data directory
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // How many days items stay in the trash, forever if unset
+    pub trash_retention_days: Option<i64>,
+    // How many actions can be undone
+    pub undo_depth: usize,
+    // How many old versions of tasks.json are kept in ~/.rtasks/backups
+    pub backup_count: usize,
+    // How long after a backup the next one is made, so saving after every change doesn't
+    // push the older backups out within a few actions
+    pub backup_interval_minutes: i64,
+    pub autosave: Autosave,
+    // Give a task the done status once every item of its checklist is checked
+    pub auto_complete_checklists: bool,
+}
+
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Autosave {
+    #[default]
+    Off,
+    // Save right after anything changes
+    OnChange,
+    // Save unsaved changes every this many seconds
+    Interval(u64),
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            trash_retention_days: Some(30),
+            undo_depth: 100,
+            backup_count: 10,
+            backup_interval_minutes: 10,
+            autosave: Autosave::default(),
+            auto_complete_checklists: false,
+        }
+    }
+}
+
+impl Config {
+    pub fn read_or_create() -> anyhow::Result<Self> {
+        let path = data_dir()?.join("config.json");
+
+        match read_existing(&path)? {
+            Some(data) => Ok(serde_json::from_str(&data)?),
+            None => {
+                let config = Config::default();
+                write_atomic(&path, serde_json::to_string_pretty(&config)?.as_bytes())?;
+                Ok(config)
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use chrono::{
+    Datelike, Days, Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday,
+};
+
+// Parse loose date input such as `2026-11-02`, `tomorrow 9:00`, `+3d` or `fri`
+pub fn parse_when(input: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    let today = Local::now().date_naive();
+    let input = input.trim().to_lowercase();
+    let mut words = input.split_whitespace().collect::<Vec<_>>();
+
+    // An optional time can come last, or stand on its own for today
+    let time = match words.last().and_then(|word| parse_time(word)) {
+        Some(time) => {
+            words.pop();
+            Some(time)
+        }
+        None => None,
+    };
+
+    let date = match words.as_slice() {
+        [] if time.is_some() => today,
+        [word] => parse_date(word, today)?,
+        _ => return None,
+    };
+
+    Some((date, time))
+}
+
+fn parse_date(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match word {
+        "today" => return Some(today),
+        "tomorrow" | "tmr" => return today.checked_add_days(Days::new(1)),
+        "yesterday" => return today.checked_sub_days(Days::new(1)),
+        _ => {}
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+        return Some(date);
+    }
+
+    if let Ok(weekday) = word.parse::<Weekday>() {
+        // Always the next one, so `mon` on a monday means a week from now
+        let ahead =
+            (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        return today.checked_add_days(Days::new(if ahead == 0 { 7 } else { ahead } as u64));
+    }
+
+    // Relative offsets like `+3d`, `-1w` or `+2m`
+    let (sign, rest) = match (word.strip_prefix('+'), word.strip_prefix('-')) {
+        (Some(rest), _) => (1, rest),
+        (_, Some(rest)) => (-1, rest),
+        _ => return None,
+    };
+    let unit = rest.chars().last()?;
+    let amount = match unit.is_ascii_digit() {
+        true => rest.parse::<u32>().ok()?,
+        false => rest.strip_suffix(unit)?.parse::<u32>().ok()?,
+    };
+
+    match (sign, unit) {
+        (1, 'm') => today.checked_add_months(Months::new(amount)),
+        (_, 'm') => today.checked_sub_months(Months::new(amount)),
+        (1, 'w') => today.checked_add_days(Days::new(amount as u64 * 7)),
+        (_, 'w') => today.checked_sub_days(Days::new(amount as u64 * 7)),
+        (1, 'd') | (1, '0'..='9') => today.checked_add_days(Days::new(amount as u64)),
+        (_, 'd') | (_, '0'..='9') => today.checked_sub_days(Days::new(amount as u64)),
+        _ => None,
+    }
+}
+
+fn parse_time(word: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(word, "%H:%M").ok()
+}
+
+pub fn format_when(date: NaiveDate, time: Option<NaiveTime>) -> String {
+    match time {
+        Some(time) => NaiveDateTime::new(date, time)
+            .format("%a %Y-%m-%d %H:%M")
+            .to_string(),
+        None => date.format("%a %Y-%m-%d").to_string(),
+    }
+}
+
+// Describe how far away a moment is, like `in 3 days` or `2 hours ago`
+pub fn relative(when: NaiveDateTime, now: NaiveDateTime) -> String {
+    let delta = when - now;
+    let (amount, unit) = match delta.num_minutes().abs() {
+        minutes if minutes < 60 => (minutes, "minute"),
+        minutes if minutes < 60 * 24 => (minutes / 60, "hour"),
+        minutes => (minutes / (60 * 24), "day"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+
+    match delta.num_minutes() >= 0 {
+        true => format!("in {} {}{}", amount, unit, plural),
+        false => format!("{} {}{} ago", amount, unit, plural),
+    }
+}
+
+// Parse an amount of time such as `2h`, `45m`, `1h30m` or `1.5h`, plain numbers are minutes
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim().to_lowercase().replace(' ', "");
+    let duration = match input.parse::<i64>() {
+        Ok(minutes) => Duration::try_minutes(minutes)?,
+        Err(_) => {
+            let (hours, rest) = match input.split_once('h') {
+                Some((hours, rest)) => (hours.parse::<f64>().ok()?, rest),
+                None => (0.0, input.as_str()),
+            };
+            let minutes = match rest.strip_suffix('m') {
+                Some(minutes) => minutes.parse::<i64>().ok()?,
+                None if rest.is_empty() => 0,
+                None => return None,
+            };
+            if !hours.is_finite() {
+                return None;
+            }
+
+            // Huge hours saturate when rounded, which try_minutes then refuses
+            Duration::try_minutes((hours * 60.0).round() as i64)?
+                .checked_add(&Duration::try_minutes(minutes)?)?
+        }
+    };
+    (duration > Duration::zero()).then_some(duration)
+}
+
+// Write a tracked amount of time, like `2h 05m`, `12m` or `40s`
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    match (seconds / 3600, seconds / 60 % 60) {
+        (0, 0) => format!("{}s", seconds),
+        (0, minutes) => format!("{}m", minutes),
+        (hours, minutes) => format!("{}h {:02}m", hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("45"), Some(Duration::minutes(45)));
+        assert_eq!(parse_duration("2h"), Some(Duration::minutes(120)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("1.5h"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration(" 1H 5M "), Some(Duration::minutes(65)));
+    }
+
+    #[test]
+    fn bad_durations() {
+        for input in [
+            "", "0", "-30", "-1h", "0h0m", "abc", "2x", "infh", "nanh", "1e300h",
+        ] {
+            assert_eq!(parse_duration(input), None, "{}", input);
+        }
+        // Too long for chrono instead of a panic
+        assert_eq!(parse_duration("999999999999999"), None);
+        assert_eq!(parse_duration("999999999999999m"), None);
+    }
+
+    #[test]
+    fn dates() {
+        let today = Local::now().date_naive();
+        let date = NaiveDate::from_ymd_opt(2026, 11, 2).unwrap();
+
+        assert_eq!(parse_when("2026-11-02"), Some((date, None)));
+        assert_eq!(
+            parse_when("2026-11-02 9:30"),
+            Some((date, NaiveTime::from_hms_opt(9, 30, 0)))
+        );
+        assert_eq!(parse_when("today"), Some((today, None)));
+        assert_eq!(parse_when("+3d"), Some((today + Days::new(3), None)));
+        assert_eq!(parse_when("-1w"), Some((today - Days::new(7), None)));
+        assert_eq!(
+            parse_when("17:00"),
+            Some((today, NaiveTime::from_hms_opt(17, 0, 0)))
+        );
+
+        // A weekday is always in the coming week
+        let (friday, _) = parse_when("fri").unwrap();
+        assert_eq!(friday.weekday(), Weekday::Fri);
+        assert!(friday > today && friday <= today + Days::new(7));
+    }
+
+    #[test]
+    fn bad_dates() {
+        for input in ["", "soon", "2026-13-01", "+d", "tomorrow at 9", "25:00"] {
+            assert_eq!(parse_when(input), None, "{}", input);
+        }
+    }
+}
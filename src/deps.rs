@@ -0,0 +1,192 @@
+use std::collections::{HashMap, HashSet};
+
+use ratatui::{prelude::*, widgets::*};
+
+use crate::task::{Folder, Id, Status, TaskRef};
+
+// Ids of the tasks waiting on a blocker that is still in the tree and not finished
+pub fn blocked(folder: &Folder, is_done: impl Fn(&Status) -> bool) -> HashSet<Id> {
+    let tasks = folder.all_tasks();
+    let open = tasks
+        .iter()
+        .filter(|found| !is_done(&found.task.status))
+        .map(|found| found.task.id)
+        .collect::<HashSet<_>>();
+
+    tasks
+        .iter()
+        .filter(|found| found.task.blocked_by.iter().any(|id| open.contains(id)))
+        .map(|found| found.task.id)
+        .collect()
+}
+
+// Whether making `task` wait on `blocker` would close a loop of tasks waiting on each other
+pub fn creates_cycle(folder: &Folder, task: Id, blocker: Id) -> bool {
+    let edges = folder
+        .all_tasks()
+        .into_iter()
+        .map(|found| (found.task.id, found.task.blocked_by.clone()))
+        .collect::<HashMap<_, _>>();
+
+    // Follow the blockers of the blocker, a loop leads back to the task
+    let mut seen = HashSet::new();
+    let mut stack = vec![blocker];
+    while let Some(id) = stack.pop() {
+        if id == task {
+            return true;
+        }
+        if seen.insert(id) {
+            stack.extend(edges.get(&id).into_iter().flatten());
+        }
+    }
+    false
+}
+
+// The tasks the picker offers as blockers, every task but the one being edited
+pub fn options(folder: &Folder, task: Id) -> Vec<TaskRef<'_>> {
+    folder
+        .all_tasks()
+        .into_iter()
+        .filter(|found| found.task.id != task)
+        .collect()
+}
+
+// A task linked to the shown one, either blocking it or blocked by it
+pub struct Link {
+    pub folders: Vec<Id>,
+    pub id: Id,
+    pub name: String,
+    pub done: bool,
+    // Whether the shown task waits on this one
+    pub upstream: bool,
+}
+
+// The blockers of the task followed by the tasks waiting on it
+pub fn links(folder: &Folder, task: Id, is_done: impl Fn(&Status) -> bool) -> Vec<Link> {
+    let tasks = folder.all_tasks();
+    let Some(shown) = tasks.iter().find(|found| found.task.id == task) else {
+        return vec![];
+    };
+
+    let link = |found: &TaskRef, upstream| Link {
+        folders: found.folders.clone(),
+        id: found.task.id,
+        name: found.full_path(),
+        done: is_done(&found.task.status),
+        upstream,
+    };
+
+    let mut links = tasks
+        .iter()
+        .filter(|found| shown.task.blocked_by.contains(&found.task.id))
+        .map(|found| link(found, true))
+        .collect::<Vec<_>>();
+    links.extend(
+        tasks
+            .iter()
+            .filter(|found| found.task.blocked_by.contains(&task))
+            .map(|found| link(found, false)),
+    );
+    links
+}
+
+// Draw the links in the details pane, highlighting the selected one while jumping
+pub fn render<B: Backend>(
+    frame: &mut Frame<B>,
+    area: Rect,
+    links: &[Link],
+    selected: Option<usize>,
+) {
+    let list = links
+        .iter()
+        .enumerate()
+        .map(|(i, link)| {
+            let arrow = match link.upstream {
+                true => "waits on",
+                false => "blocks  ",
+            };
+            let style = match link.done {
+                true => Style::default().fg(Color::DarkGray),
+                false => Style::default(),
+            };
+
+            let item = ListItem::new(format!("{} {}", arrow, link.name));
+            match Some(i) == selected {
+                true => item.style(style.bg(Color::DarkGray).fg(Color::White)),
+                false => item.style(style),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let title = match selected {
+        Some(_) => "Dependencies  <enter> JUMP  <esc> BACK",
+        None => "Dependencies",
+    };
+
+    let list = List::new(list).block(
+        Block::new()
+            .title(title)
+            .borders(border!(TOP))
+            .border_type(BorderType::Plain),
+    );
+
+    frame.render_widget(list, area);
+}
+
+// Every other task to toggle whether the edited task waits on it
+pub fn picker_items(folder: &Folder, task: Id) -> Vec<ListItem<'static>> {
+    let blockers = folder
+        .all_tasks()
+        .into_iter()
+        .find(|found| found.task.id == task)
+        .map(|found| found.task.blocked_by.clone())
+        .unwrap_or_default();
+
+    options(folder, task)
+        .iter()
+        .map(|found| {
+            let mark = match blockers.contains(&found.task.id) {
+                true => "[x]",
+                false => "[ ]",
+            };
+            ListItem::new(format!("{} {}", mark, found.full_path()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Item, Task};
+
+    fn task(folder: &mut Folder, blocked_by: Vec<Id>) -> Id {
+        let task = Task {
+            blocked_by,
+            ..Task::default()
+        };
+        let id = task.id;
+        folder.insert(Item::Task(task));
+        id
+    }
+
+    #[test]
+    fn cycles() {
+        // c waits on b, which waits on a. c is in another folder
+        let mut folder = Folder::new();
+        let a = task(&mut folder, vec![]);
+        let b = task(&mut folder, vec![a]);
+        let c = task(folder.new_folder("sub".to_string()), vec![b]);
+
+        // Directly and through b
+        assert!(creates_cycle(&folder, a, b));
+        assert!(creates_cycle(&folder, a, c));
+        assert!(creates_cycle(&folder, a, a));
+
+        // Waiting on a task further up the chain is fine, so is a second way to the same one
+        assert!(!creates_cycle(&folder, c, a));
+        assert!(!creates_cycle(&folder, b, a));
+        let d = task(&mut folder, vec![]);
+        assert!(!creates_cycle(&folder, d, c));
+        assert!(!creates_cycle(&folder, c, d));
+    }
+}
@@ -0,0 +1,260 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+
+// A multi-line text area for a task's details, wrapping words to the width it is drawn at
+#[derive(Default)]
+pub struct TextArea {
+    lines: Vec<String>,
+    // The cursor, as a line and a character in it
+    row: usize,
+    col: usize,
+    // The first wrapped row shown
+    scroll: usize,
+    // The size of the text when it was last drawn, for moving by screen rows
+    width: usize,
+    height: usize,
+}
+
+impl TextArea {
+    // Start editing the text with the cursor at its end
+    pub fn new(text: &str) -> Self {
+        let lines = text.split('\n').map(String::from).collect::<Vec<_>>();
+        let row = lines.len() - 1;
+        let col = lines[row].chars().count();
+
+        Self {
+            lines,
+            row,
+            col,
+            width: 80,
+            height: 10,
+            ..Self::default()
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.lines[row].chars().count()
+    }
+
+    // Where the cursor is in the bytes of its line
+    fn byte(&self, col: usize) -> usize {
+        self.lines[self.row]
+            .char_indices()
+            .nth(col)
+            .map_or(self.lines[self.row].len(), |(i, _)| i)
+    }
+
+    fn insert(&mut self, text: &str) {
+        let at = self.byte(self.col);
+        self.lines[self.row].insert_str(at, text);
+        self.col += text.chars().count();
+    }
+
+    fn newline(&mut self) {
+        let at = self.byte(self.col);
+        let rest = self.lines[self.row].split_off(at);
+        self.lines.insert(self.row + 1, rest);
+        (self.row, self.col) = (self.row + 1, 0);
+    }
+
+    // Delete the characters of the line between the two columns, leaving the cursor at the first
+    fn delete(&mut self, from: usize, to: usize) {
+        let (start, end) = (self.byte(from), self.byte(to));
+        self.lines[self.row].replace_range(start..end, "");
+        self.col = from;
+    }
+
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.delete(self.col - 1, self.col);
+        } else if self.row > 0 {
+            // Join the line onto the one above
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len(self.row);
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    fn delete_forward(&mut self) {
+        if self.col < self.line_len(self.row) {
+            self.delete(self.col, self.col + 1);
+        } else if self.row + 1 < self.lines.len() {
+            let line = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    // The column where the word before the cursor starts
+    fn word_start(&self) -> usize {
+        let chars = self.lines[self.row].chars().collect::<Vec<_>>();
+        let mut col = self.col;
+        while col > 0 && chars[col - 1].is_whitespace() {
+            col -= 1;
+        }
+        while col > 0 && !chars[col - 1].is_whitespace() {
+            col -= 1;
+        }
+        col
+    }
+
+    // The column where the word after the cursor ends
+    fn word_end(&self) -> usize {
+        let chars = self.lines[self.row].chars().collect::<Vec<_>>();
+        let mut col = self.col;
+        while col < chars.len() && chars[col].is_whitespace() {
+            col += 1;
+        }
+        while col < chars.len() && !chars[col].is_whitespace() {
+            col += 1;
+        }
+        col
+    }
+
+    fn left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line_len(self.row);
+        }
+    }
+
+    fn right(&mut self) {
+        if self.col < self.line_len(self.row) {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            (self.row, self.col) = (self.row + 1, 0);
+        }
+    }
+
+    // Move the cursor by wrapped rows, keeping it as close to the same column as it can
+    fn vertical(&mut self, rows: isize) {
+        let wrapped = self.wrapped();
+        let (at, x) = self.cursor(&wrapped);
+        let target = (at as isize + rows).clamp(0, wrapped.len() as isize - 1) as usize;
+
+        let (row, start, end) = wrapped[target];
+        let last = wrapped.get(target + 1).is_none_or(|next| next.0 != row);
+        // Only the last piece of a line can have the cursor after its end
+        let end = match last {
+            true => end,
+            false => end - 1,
+        };
+        (self.row, self.col) = (row, (start + x).min(end));
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('a') if ctrl => self.col = 0,
+            KeyCode::Char('e') if ctrl => self.col = self.line_len(self.row),
+            KeyCode::Char('k') if ctrl => match self.col == self.line_len(self.row) {
+                true => self.delete_forward(),
+                false => self.delete(self.col, self.line_len(self.row)),
+            },
+            KeyCode::Char('u') if ctrl => self.delete(0, self.col),
+            KeyCode::Char('w') if ctrl => self.delete(self.word_start(), self.col),
+            KeyCode::Backspace if ctrl => self.delete(self.word_start(), self.col),
+            KeyCode::Left if ctrl => self.col = self.word_start(),
+            KeyCode::Right if ctrl => self.col = self.word_end(),
+            KeyCode::Home if ctrl => (self.row, self.col) = (0, 0),
+            KeyCode::End if ctrl => {
+                self.row = self.lines.len() - 1;
+                self.col = self.line_len(self.row);
+            }
+            KeyCode::Char(_) if ctrl => {}
+            KeyCode::Char(c) => self.insert(&c.to_string()),
+            KeyCode::Tab => self.insert("    "),
+            KeyCode::Enter => self.newline(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete_forward(),
+            KeyCode::Left => self.left(),
+            KeyCode::Right => self.right(),
+            KeyCode::Up => self.vertical(-1),
+            KeyCode::Down => self.vertical(1),
+            KeyCode::PageUp => self.vertical(-(self.height as isize)),
+            KeyCode::PageDown => self.vertical(self.height as isize),
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(self.row),
+            _ => {}
+        }
+    }
+
+    // Break the lines into rows that fit the width, at spaces where possible.
+    // Each row is its line and the range of characters it shows
+    fn wrapped(&self) -> Vec<(usize, usize, usize)> {
+        let width = self.width.max(1);
+        let mut rows = vec![];
+        for (row, line) in self.lines.iter().enumerate() {
+            let chars = line.chars().collect::<Vec<_>>();
+            let mut start = 0;
+            while chars.len() - start > width {
+                let end = match chars[start..start + width]
+                    .iter()
+                    .rposition(|c| c.is_whitespace())
+                {
+                    Some(space) if space > 0 => start + space + 1,
+                    _ => start + width,
+                };
+                rows.push((row, start, end));
+                start = end;
+            }
+            rows.push((row, start, chars.len()));
+        }
+        rows
+    }
+
+    // The wrapped row the cursor is on and how far into it
+    fn cursor(&self, wrapped: &[(usize, usize, usize)]) -> (usize, usize) {
+        let at = wrapped
+            .iter()
+            .rposition(|(row, start, _)| *row == self.row && *start <= self.col)
+            .unwrap_or(0);
+        (at, self.col - wrapped[at].1)
+    }
+
+    pub fn render<B: Backend>(&mut self, frame: &mut Frame<B>, area: Rect, title: &str) {
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded);
+        let inner = block.inner(area);
+        (self.width, self.height) = (inner.width.max(1) as usize, inner.height.max(1) as usize);
+
+        let wrapped = self.wrapped();
+        let (at, x) = self.cursor(&wrapped);
+
+        // Scroll just enough to keep the cursor in view
+        if at < self.scroll {
+            self.scroll = at;
+        } else if at >= self.scroll + self.height {
+            self.scroll = at + 1 - self.height;
+        }
+
+        let text = wrapped
+            .iter()
+            .skip(self.scroll)
+            .take(self.height)
+            .map(|(row, start, end)| {
+                let line = self.lines[*row]
+                    .chars()
+                    .skip(*start)
+                    .take(end - start)
+                    .collect::<String>();
+                Line::from(line)
+            })
+            .collect::<Vec<_>>();
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+        frame.set_cursor(
+            inner.x + (x as u16).min(inner.width.saturating_sub(1)),
+            inner.y + (at - self.scroll) as u16,
+        );
+    }
+}
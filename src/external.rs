@@ -0,0 +1,202 @@
+use std::{env, fs, io::Stdout, path::Path, process::Command};
+
+use ratatui::{prelude::CrosstermBackend, Terminal};
+
+use crate::{
+    dates::parse_when,
+    setup::{restore_terminal, setup_terminal},
+    status::{StatusDef, Statuses},
+    tags,
+    task::Task,
+};
+
+// Marks the start and end of the header with the fields of the task
+const FENCE: &str = "---";
+
+// Edit the task in $VISUAL or $EDITOR, suspending the interface until the editor exits.
+// Returns the edited task, if reading it back fails the file is kept to not lose the edit
+pub fn edit_task(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    task: &Task,
+    statuses: &Statuses,
+) -> anyhow::Result<Task> {
+    let path = env::temp_dir().join(format!("rtasks-{}.md", task.id));
+    fs::write(&path, to_text(task))?;
+
+    restore_terminal(terminal).map_err(|e| anyhow!("{}", e))?;
+    let edited = run_editor(&path);
+    *terminal = setup_terminal().map_err(|e| anyhow!("{}", e))?;
+    edited?;
+
+    let edited = fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|text| from_text(task, &text, statuses))
+        .map_err(|e| anyhow!("{}, the edit is kept in {}", e, path.display()))?;
+
+    fs::remove_file(&path)?;
+    Ok(edited)
+}
+
+// $VISUAL before $EDITOR, falling back to vi. Either can carry arguments like `code --wait`
+fn run_editor(path: &Path) -> anyhow::Result<()> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| String::from("vi"));
+
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .map_err(|e| anyhow!("Couldn't start {}: {}", program, e))?;
+
+    match status.success() {
+        true => Ok(()),
+        false => Err(anyhow!("{} exited with {}", program, status)),
+    }
+}
+
+// The header followed by the details
+fn to_text(task: &Task) -> String {
+    let due = match (task.due, task.due_time) {
+        (Some(date), Some(time)) => format!("{} {}", date.format("%Y-%m-%d"), time.format("%H:%M")),
+        (Some(date), None) => date.format("%Y-%m-%d").to_string(),
+        (None, _) => String::new(),
+    };
+
+    format!(
+        "{FENCE}\ntitle: {}\nstatus: {}\ntags: {}\ndue: {}\n{FENCE}\n{}\n",
+        task.title,
+        task.status.status,
+        task.tags.iter().cloned().collect::<Vec<_>>().join(" "),
+        due,
+        task.task
+    )
+}
+
+// Read the file back onto the task. The header is optional and so is each of its fields,
+// the ones left out stay as they were
+fn from_text(task: &Task, text: &str, statuses: &Statuses) -> anyhow::Result<Task> {
+    let mut task = task.clone();
+    let text = text.trim_end_matches('\n');
+
+    let details = match text
+        .strip_prefix(FENCE)
+        .filter(|rest| rest.starts_with('\n'))
+    {
+        Some(rest) => {
+            let mut lines = rest[1..].split('\n');
+            for line in lines.by_ref() {
+                if line.trim_end() == FENCE {
+                    break;
+                }
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let (key, value) = line
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("Couldn't read the header line {}", line))?;
+                read_field(&mut task, key.trim(), value.trim(), statuses)?;
+            }
+            lines.collect::<Vec<_>>().join("\n")
+        }
+        None => text.to_string(),
+    };
+
+    task.task = details;
+    Ok(task)
+}
+
+fn read_field(task: &mut Task, key: &str, value: &str, statuses: &Statuses) -> anyhow::Result<()> {
+    match key {
+        "title" if value.is_empty() => return Err(anyhow!("The title can't be empty")),
+        "title" => task.title = value.to_string(),
+        // Statuses typed in before aren't defined, they stay as long as they aren't changed
+        "status" if value == task.status.status => {}
+        "status" => {
+            // The same statuses the picker would offer
+            let allowed = statuses
+                .options(Some(&task.status))
+                .into_iter()
+                .find(|def| def.name.eq_ignore_ascii_case(value))
+                .map(StatusDef::to_status);
+            task.status = match (allowed, statuses.named(value)) {
+                (Some(status), _) => status,
+                (None, Some(_)) => {
+                    return Err(anyhow!("{} can't go to {}", task.status.status, value))
+                }
+                (None, None) => return Err(anyhow!("There is no status called {}", value)),
+            };
+        }
+        "tags" => task.tags = tags::parse(value),
+        "due" if value.is_empty() => (task.due, task.due_time) = (None, None),
+        "due" => {
+            let (date, time) = parse_when(value)
+                .ok_or_else(|| anyhow!("Couldn't read {} as a due date", value))?;
+            (task.due, task.due_time) = (Some(date), time);
+        }
+        _ => return Err(anyhow!("Unknown header field {}", key)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Status;
+
+    fn statuses() -> Statuses {
+        serde_json::from_str(
+            r#"[
+                { "name": "Incomplete", "color": 5, "next": ["In Progress"] },
+                { "name": "In Progress", "color": 3 },
+                { "name": "Complete", "color": 2, "done": true }
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    fn task(status: &str) -> Task {
+        Task {
+            title: "Report".to_string(),
+            status: Status {
+                status: status.to_string(),
+                color: 1,
+            },
+            ..Task::default()
+        }
+    }
+
+    #[test]
+    fn reads_back_what_it_wrote() {
+        let task = task("Waiting on Sam");
+        let edited = from_text(&task, &to_text(&task), &statuses()).unwrap();
+        assert!(edited == task);
+    }
+
+    #[test]
+    fn header_is_optional() {
+        let edited = from_text(&task("Incomplete"), "just details\n", &statuses()).unwrap();
+        assert_eq!(edited.title, "Report");
+        assert_eq!(edited.task, "just details");
+    }
+
+    #[test]
+    fn statuses_follow_the_rules() {
+        let text = |status: &str| format!("---\nstatus: {}\n---\n", status);
+
+        let edited = from_text(&task("Incomplete"), &text("in progress"), &statuses()).unwrap();
+        assert_eq!(edited.status.status, "In Progress");
+
+        // Incomplete can only go to In Progress
+        assert!(from_text(&task("Incomplete"), &text("Complete"), &statuses()).is_err());
+        assert!(from_text(&task("Incomplete"), &text("Nope"), &statuses()).is_err());
+        // Undefined statuses can go anywhere
+        let edited = from_text(&task("Waiting"), &text("Complete"), &statuses()).unwrap();
+        assert_eq!(edited.status.status, "Complete");
+    }
+}
@@ -0,0 +1,87 @@
+use crossterm::event::KeyCode;
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{
+    found::Highlight,
+    markdown::Scroll,
+    render_task_details,
+    task::{Folder, Id, Task, TaskRef},
+    Chunks,
+};
+
+#[derive(Default)]
+pub struct Filter {
+    pub query: String,
+    highlight: Highlight,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Every task in the tree that matches each word of the query
+    pub fn results<'a>(folder: &'a Folder, query: &str) -> Vec<TaskRef<'a>> {
+        let terms = query
+            .split_whitespace()
+            .map(|term| term.to_lowercase())
+            .collect::<Vec<_>>();
+
+        folder
+            .all_tasks()
+            .into_iter()
+            .filter(|found| terms.iter().all(|term| matches(found.task, term)))
+            .collect()
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) {
+        self.highlight.handle_key(key)
+    }
+
+    pub fn selected_result(&self, folder: &Folder) -> Option<(Vec<Id>, Id)> {
+        let results = Self::results(folder, &self.query);
+        self.highlight.target(&results.iter().collect::<Vec<_>>())
+    }
+
+    pub fn render<B: Backend>(
+        &mut self,
+        frame: &mut Frame<B>,
+        chunks: &Chunks,
+        folder: &Folder,
+        query: &str,
+        scroll: &mut Scroll,
+    ) {
+        let results = Self::results(folder, query);
+        let selected = self.highlight.row(results.len());
+
+        let list = results
+            .iter()
+            .enumerate()
+            .map(|(i, found)| match i == selected {
+                true => found
+                    .to_list_item()
+                    .style(Style::default().bg(Color::DarkGray)),
+                false => found.to_list_item(),
+            })
+            .collect::<Vec<_>>();
+
+        let list = List::new(list).block(
+            Block::default()
+                .title(format!("Filter: {} ({} found)", query, results.len()))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain),
+        );
+
+        frame.render_widget(list, chunks.left_menu());
+
+        if let Some(found) = results.get(selected) {
+            render_task_details(frame, chunks, found.task, scroll);
+        }
+    }
+}
+
+fn matches(task: &Task, term: &str) -> bool {
+    task.title.to_lowercase().contains(term)
+        || task.task.to_lowercase().contains(term)
+        || task.status.status.to_lowercase().contains(term)
+}
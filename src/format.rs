@@ -0,0 +1,150 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::task::{Folder, Id};
+
+// The version of tasks.json this build writes
+pub const VERSION: u64 = 3;
+
+// Each migration upgrades a file from the version at its index + 1 to the next one
+const MIGRATIONS: [fn(Value) -> anyhow::Result<Value>; 2] = [v1_to_v2, v2_to_v3];
+
+#[derive(Serialize)]
+struct Versioned<'a> {
+    version: u64,
+    #[serde(flatten)]
+    folder: &'a Folder,
+}
+
+pub fn to_string(folder: &Folder) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(&Versioned {
+        version: VERSION,
+        folder,
+    })?)
+}
+
+// Read a tasks.json of any known version, migrating it up to the current one
+pub fn parse(data: &str) -> anyhow::Result<Folder> {
+    let mut value: Value = serde_json::from_str(data)?;
+    if !value.is_object() {
+        return Err(anyhow!("tasks.json should contain a folder object"));
+    }
+
+    // Files from before versioning have no version field
+    let mut version = match value.get("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| anyhow!("tasks.json has {} as its version", version))?,
+    };
+    if version == 0 {
+        return Err(anyhow!(
+            "tasks.json has 0 as its version, versions start at 1"
+        ));
+    }
+    if version > VERSION {
+        return Err(anyhow!(
+            "tasks.json is version {}, but this version of rtui only understands up to version {}",
+            version,
+            VERSION
+        ));
+    }
+
+    // Parsing the text directly keeps the line and column of any errors correct
+    if version == VERSION {
+        return Ok(serde_json::from_str(data)?);
+    }
+
+    while version < VERSION {
+        value = MIGRATIONS[version as usize - 1](value)?;
+        version += 1;
+        value["version"] = version.into();
+    }
+
+    Ok(serde_json::from_value(value)?)
+}
+
+// Version 2 only added the version field itself
+fn v1_to_v2(value: Value) -> anyhow::Result<Value> {
+    Ok(value)
+}
+
+// Version 3 gave every task and folder an id
+fn v2_to_v3(mut value: Value) -> anyhow::Result<Value> {
+    add_ids(&mut value);
+    Ok(value)
+}
+
+fn add_ids(folder: &mut Value) {
+    if let Some(folder) = folder.as_object_mut() {
+        folder
+            .entry("id")
+            .or_insert_with(|| Id::default().to_string().into());
+    }
+
+    if let Some(tasks) = folder.get_mut("tasks").and_then(Value::as_array_mut) {
+        for task in tasks.iter_mut().filter_map(Value::as_object_mut) {
+            task.entry("id")
+                .or_insert_with(|| Id::default().to_string().into());
+        }
+    }
+
+    if let Some(folders) = folder.get_mut("folders").and_then(Value::as_array_mut) {
+        folders.iter_mut().for_each(add_ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    const TREE: &str = r#""name": "",
+        "tasks": [
+            {
+                "title": "Report",
+                "task": "",
+                "status": { "status": "Incomplete", "color": 5 }
+            }
+        ],
+        "folders": [{ "name": "Work", "tasks": [], "folders": [] }]"#;
+
+    fn ids(folder: &Folder) -> HashSet<Id> {
+        let mut ids = folder
+            .all_folders()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<HashSet<_>>();
+        ids.extend(folder.all_tasks().iter().map(|found| found.task.id));
+        ids
+    }
+
+    #[test]
+    fn old_versions_migrate() {
+        for data in [
+            format!("{{ {} }}", TREE),
+            format!("{{ \"version\": 2, {} }}", TREE),
+        ] {
+            let folder = parse(&data).unwrap();
+            assert_eq!(folder.all_tasks()[0].task.title, "Report");
+            // The root, Work and Report each got their own id
+            assert_eq!(ids(&folder).len(), 3);
+        }
+    }
+
+    #[test]
+    fn current_version_keeps_ids() {
+        let folder = parse(&format!("{{ {} }}", TREE)).unwrap();
+        let again = parse(&to_string(&folder).unwrap()).unwrap();
+        assert!(ids(&folder) == ids(&again));
+    }
+
+    #[test]
+    fn unknown_versions_are_refused() {
+        for version in ["0", "4", "-1", "\"3\""] {
+            let data = format!("{{ \"version\": {}, {} }}", version, TREE);
+            assert!(parse(&data).is_err(), "{}", version);
+        }
+    }
+}
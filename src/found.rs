@@ -0,0 +1,32 @@
+use crossterm::event::KeyCode;
+
+use crate::task::{Id, TaskRef};
+
+// The highlighted row of a list of tasks found across the tree, like the filter results.
+// The list can change between frames, so the row is kept inside it whenever it is read
+#[derive(Default)]
+pub struct Highlight {
+    selected: usize,
+}
+
+impl Highlight {
+    pub fn handle_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Down => self.selected += 1,
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    // The row to highlight in a list of len tasks
+    pub fn row(&mut self, len: usize) -> usize {
+        self.selected = self.selected.min(len.saturating_sub(1));
+        self.selected
+    }
+
+    // Find the folder path and id of the highlighted task, so the list can jump to it
+    pub fn target(&self, tasks: &[&TaskRef]) -> Option<(Vec<Id>, Id)> {
+        let found = tasks.get(self.selected.min(tasks.len().checked_sub(1)?))?;
+        Some((found.folders.clone(), found.task.id))
+    }
+}
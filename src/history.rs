@@ -0,0 +1,414 @@
+use std::collections::VecDeque;
+
+use crate::{
+    store::{Store, StoredItem},
+    task::{Folder, Id, Item, SortMode, Task, TaskFile},
+};
+
+// A single reversible change to the folder tree or the stores.
+// Folders and tasks are found by id, so edits still apply after renames
+pub enum Edit {
+    // The item was added to the folder with the parent id
+    Insert {
+        parent: Id,
+        index: usize,
+        item: Item,
+    },
+    // The item was taken out of the folder and pushed onto the end of a store
+    Delete {
+        parent: Id,
+        path: Vec<String>,
+        index: usize,
+        item: Item,
+        file: TaskFile,
+    },
+    // The item was taken out of a store and put back into the folder with the parent id
+    Restore {
+        file: TaskFile,
+        store_index: usize,
+        stored: StoredItem,
+        parent: Id,
+        index: usize,
+    },
+    // The item was moved from one folder and position to another, or within the same folder
+    Move {
+        id: Id,
+        from: Id,
+        from_index: usize,
+        to: Id,
+        to_index: usize,
+    },
+    Rename {
+        id: Id,
+        from: String,
+        to: String,
+    },
+    // Boxed, tasks are much bigger than the other edits
+    Task {
+        from: Box<Task>,
+        to: Box<Task>,
+    },
+    Sort {
+        id: Id,
+        from: SortMode,
+        to: SortMode,
+    },
+}
+
+impl Edit {
+    pub fn task(from: Task, to: Task) -> Self {
+        Edit::Task {
+            from: Box::new(from),
+            to: Box::new(to),
+        }
+    }
+
+    fn undo(&self, folder: &mut Folder, trash: &mut Store, complete: &mut Store) -> Option<()> {
+        match self {
+            Edit::Insert { parent, item, .. } => {
+                folder.find_folder(*parent)?.remove(item.id())?;
+            }
+            Edit::Delete {
+                parent,
+                index,
+                item,
+                file,
+                ..
+            } => {
+                store(*file, trash, complete).take(item.id())?;
+                folder.find_folder(*parent)?.insert_at(*index, item.clone());
+            }
+            Edit::Restore {
+                file,
+                store_index,
+                stored,
+                parent,
+                ..
+            } => {
+                folder.find_folder(*parent)?.remove(stored.item.id())?;
+                store(*file, trash, complete).insert(*store_index, stored.clone());
+            }
+            Edit::Move {
+                id,
+                from,
+                from_index,
+                to,
+                ..
+            } => {
+                folder.move_item(*id, *to, *from, Some(*from_index))?;
+            }
+            Edit::Rename { id, from, .. } => {
+                folder.find_folder(*id)?.name = from.clone();
+            }
+            Edit::Task { from, .. } => {
+                *folder.find_task(from.id)? = (**from).clone();
+            }
+            Edit::Sort { id, from, .. } => {
+                folder.find_folder(*id)?.sort = *from;
+            }
+        }
+        Some(())
+    }
+
+    fn redo(&self, folder: &mut Folder, trash: &mut Store, complete: &mut Store) -> Option<()> {
+        match self {
+            Edit::Insert {
+                parent,
+                index,
+                item,
+            } => {
+                folder.find_folder(*parent)?.insert_at(*index, item.clone());
+            }
+            Edit::Delete {
+                parent,
+                path,
+                item,
+                file,
+                ..
+            } => {
+                let item = folder.find_folder(*parent)?.remove(item.id())?;
+                store(*file, trash, complete).push(*parent, path.clone(), item);
+            }
+            Edit::Restore {
+                file,
+                store_index,
+                stored,
+                parent,
+                index,
+            } => {
+                store(*file, trash, complete).remove(*store_index)?;
+                folder
+                    .find_folder(*parent)?
+                    .insert_at(*index, stored.item.clone());
+            }
+            Edit::Move {
+                id,
+                from,
+                to,
+                to_index,
+                ..
+            } => {
+                folder.move_item(*id, *from, *to, Some(*to_index))?;
+            }
+            Edit::Rename { id, to, .. } => {
+                folder.find_folder(*id)?.name = to.clone();
+            }
+            Edit::Task { to, .. } => {
+                *folder.find_task(to.id)? = (**to).clone();
+            }
+            Edit::Sort { id, to, .. } => {
+                folder.find_folder(*id)?.sort = *to;
+            }
+        }
+        Some(())
+    }
+}
+
+fn store<'a>(file: TaskFile, trash: &'a mut Store, complete: &'a mut Store) -> &'a mut Store {
+    match file {
+        TaskFile::Complete => complete,
+        _ => trash,
+    }
+}
+
+// A group of edits made by one action, undone and redone together
+pub struct Command {
+    description: String,
+    edits: Vec<Edit>,
+}
+
+// The undo and redo stacks for this session
+pub struct History {
+    depth: usize,
+    undo: VecDeque<Command>,
+    redo: Vec<Command>,
+    // Bumped on every change, so callers can tell whether anything happened since they last looked
+    version: usize,
+}
+
+impl History {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            undo: VecDeque::new(),
+            redo: vec![],
+            version: 0,
+        }
+    }
+
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    pub fn record(&mut self, description: impl Into<String>, edits: Vec<Edit>) {
+        if edits.is_empty() {
+            return;
+        }
+
+        self.version += 1;
+        if self.depth == 0 {
+            return;
+        }
+
+        self.redo.clear();
+        self.undo.push_back(Command {
+            description: description.into(),
+            edits,
+        });
+
+        while self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+    }
+
+    // Add edits that follow from the last command to it, so they are undone together
+    pub fn amend(&mut self, description: &str, edits: Vec<Edit>) {
+        if edits.is_empty() {
+            return;
+        }
+
+        match self.undo.back_mut() {
+            Some(command) if self.redo.is_empty() => {
+                self.version += 1;
+                command.edits.extend(edits);
+            }
+            _ => self.record(description, edits),
+        }
+    }
+
+    // Forget everything, used when a change is made that can't be undone
+    pub fn clear(&mut self) {
+        self.version += 1;
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    // Undo the last command, returning what was undone
+    pub fn undo(
+        &mut self,
+        folder: &mut Folder,
+        trash: &mut Store,
+        complete: &mut Store,
+    ) -> Option<String> {
+        let command = self.undo.pop_back()?;
+        self.version += 1;
+        for edit in command.edits.iter().rev() {
+            edit.undo(folder, trash, complete);
+        }
+
+        let description = command.description.clone();
+        self.redo.push(command);
+        Some(description)
+    }
+
+    pub fn redo(
+        &mut self,
+        folder: &mut Folder,
+        trash: &mut Store,
+        complete: &mut Store,
+    ) -> Option<String> {
+        let command = self.redo.pop()?;
+        self.version += 1;
+        for edit in &command.edits {
+            edit.redo(folder, trash, complete);
+        }
+
+        let description = command.description.clone();
+        self.undo.push_back(command);
+        Some(description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn task(title: &str) -> Item {
+        Item::Task(Task {
+            title: title.to_string(),
+            ..Task::default()
+        })
+    }
+
+    // Take the item out of the folder into a store and record it, the way deleting does
+    fn delete(folder: &mut Folder, store: &mut Store, file: TaskFile, id: Id) -> Edit {
+        let index = folder.position(id).unwrap();
+        let item = folder.remove(id).unwrap();
+        store.push(folder.id, vec![], item.clone());
+        Edit::Delete {
+            parent: folder.id,
+            path: vec![],
+            index,
+            item,
+            file,
+        }
+    }
+
+    #[test]
+    fn delete_undo_redo() {
+        let mut folder = Folder::new();
+        let (trashed, done) = (task("a"), task("b"));
+        let (trash_id, done_id) = (trashed.id(), done.id());
+        folder.insert(trashed);
+        folder.insert(done);
+        let mut trash = Store::new(TaskFile::Trash);
+        let mut complete = Store::new(TaskFile::Complete);
+        let mut history = History::new(10);
+
+        let edit = delete(&mut folder, &mut trash, TaskFile::Trash, trash_id);
+        history.record("delete", vec![edit]);
+        let edit = delete(&mut folder, &mut complete, TaskFile::Complete, done_id);
+        history.record("complete", vec![edit]);
+        assert!(folder.ids().len() == 1);
+
+        history.undo(&mut folder, &mut trash, &mut complete);
+        history.undo(&mut folder, &mut trash, &mut complete);
+        assert!(folder.position(trash_id) == Some(0));
+        assert!(folder.position(done_id) == Some(1));
+        assert!(trash.ids().is_empty() && complete.ids().is_empty());
+
+        history.redo(&mut folder, &mut trash, &mut complete);
+        history.redo(&mut folder, &mut trash, &mut complete);
+        assert!(trash.ids() == HashSet::from([trash_id]));
+        assert!(complete.ids() == HashSet::from([done_id]));
+        assert!(folder.ids().len() == 1);
+    }
+
+    #[test]
+    fn undo_takes_back_the_deleted_item() {
+        let mut folder = Folder::new();
+        let item = task("a");
+        let id = item.id();
+        folder.insert(item);
+        let mut trash = Store::new(TaskFile::Trash);
+        let mut complete = Store::new(TaskFile::Complete);
+        let mut history = History::new(10);
+
+        let edit = delete(&mut folder, &mut trash, TaskFile::Trash, id);
+        history.record("delete", vec![edit]);
+        // Something else ended up on top of the trash without going through the history
+        let other = task("other");
+        let other_id = other.id();
+        trash.push(folder.id, vec![], other);
+
+        history.undo(&mut folder, &mut trash, &mut complete);
+        assert!(folder.position(id) == Some(0));
+        assert!(trash.ids() == HashSet::from([other_id]));
+    }
+
+    #[test]
+    fn restore_undo_redo() {
+        let mut folder = Folder::new();
+        let item = task("a");
+        let id = item.id();
+        let mut trash = Store::new(TaskFile::Trash);
+        let mut complete = Store::new(TaskFile::Complete);
+        trash.push(folder.id, vec![], item);
+        let mut history = History::new(10);
+
+        trash.restore_selected(&mut folder, &mut history);
+        assert!(folder.position(id) == Some(0) && trash.ids().is_empty());
+
+        history.undo(&mut folder, &mut trash, &mut complete);
+        assert!(folder.position(id).is_none());
+        assert!(trash.ids() == HashSet::from([id]));
+
+        history.redo(&mut folder, &mut trash, &mut complete);
+        assert!(folder.position(id) == Some(0) && trash.ids().is_empty());
+    }
+
+    #[test]
+    fn undoing_a_move_puts_it_back_in_place() {
+        let mut folder = Folder::new();
+        let from = folder.new_folder("from".to_string()).id;
+        let to = folder.new_folder("to".to_string()).id;
+        for title in ["a", "b", "c"] {
+            folder.find_folder(from).unwrap().insert(task(title));
+        }
+        let id = folder.find_folder(from).unwrap().id_at(1).unwrap();
+        let mut trash = Store::new(TaskFile::Trash);
+        let mut complete = Store::new(TaskFile::Complete);
+        let mut history = History::new(10);
+
+        let to_index = folder.move_item(id, from, to, None).unwrap();
+        history.record(
+            "move",
+            vec![Edit::Move {
+                id,
+                from,
+                from_index: 1,
+                to,
+                to_index,
+            }],
+        );
+
+        history.undo(&mut folder, &mut trash, &mut complete);
+        assert!(folder.find_folder(from).unwrap().position(id) == Some(1));
+        assert!(folder.find_folder(to).unwrap().position(id).is_none());
+
+        history.redo(&mut folder, &mut trash, &mut complete);
+        assert!(folder.find_folder(to).unwrap().position(id) == Some(0));
+    }
+}
@@ -1,10 +1,34 @@
-use std::{error::Error, io::Stdout, rc::Rc, time::Duration};
+use std::{
+    collections::HashSet,
+    env,
+    error::Error,
+    fs,
+    io::Stdout,
+    path::PathBuf,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
-use crossterm::event::{self, Event, KeyCode};
+use calendar::Calendar;
+use chrono::Local;
+use clipboard::Clipboard;
+use config::{Autosave, Config};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use dates::{format_duration, format_when, parse_duration, parse_when, relative};
+use editor::TextArea;
+use filter::Filter;
+use history::{Edit, History};
+use markdown::Scroll;
 use ratatui::{prelude::*, text::Line, widgets::*};
+use recur::Recurrence;
 use setup::{restore_terminal, setup_terminal};
+use status::{StatusDef, Statuses};
+use store::Store;
+use tags::TagBrowser;
 use task::*;
+use timesheet::Timesheet;
 use tui_input::backend::crossterm::EventHandler;
+use workspace::Workspace;
 
 #[macro_use]
 extern crate ratatui;
@@ -12,15 +36,49 @@ extern crate ratatui;
 #[macro_use]
 extern crate anyhow;
 
+mod backup;
+mod calendar;
+mod checklist;
+mod clipboard;
+mod config;
+mod dates;
+mod deps;
+mod editor;
+mod external;
+mod filter;
+mod format;
+mod found;
+mod history;
+mod markdown;
+mod recovery;
+mod recur;
 mod setup;
+mod status;
+mod store;
+mod tags;
 mod task;
+mod timesheet;
+mod workspace;
+
+const TABS: [&str; 7] = [
+    "[TAB]  List",
+    "Calendar",
+    "Filter",
+    "Tags",
+    "Time",
+    "Trash",
+    "Complete",
+];
 
 #[derive(Copy, Clone)]
 pub enum TaskStep {
     Title,
     Details,
-    Status,
-    StatusColor,
+    Due,
+    Start,
+    Tags,
+    Repeat,
+    Estimate,
 }
 
 impl TaskStep {
@@ -28,8 +86,13 @@ impl TaskStep {
         match self {
             TaskStep::Title => "Please input title",
             TaskStep::Details => "Please input details",
-            TaskStep::Status => "Please input status",
-            TaskStep::StatusColor => "Please input ansii color code",
+            TaskStep::Due => "Please input due date (2026-11-02, tomorrow 9:00, +3d, fri)",
+            TaskStep::Start => "Please input start date (2026-11-02, tomorrow 9:00, +3d, fri)",
+            TaskStep::Tags => "Please input tags separated by spaces, <tab> completes",
+            TaskStep::Estimate => "Please input the estimate (2h, 45m, 1h30m), empty for none",
+            TaskStep::Repeat => {
+                "Please input how it repeats (daily, weekly mon thu, monthly 15, every 3 days)"
+            }
         }
     }
 }
@@ -40,6 +103,13 @@ pub enum InputStatus {
     Request(InputRequestType),
     New,
     Edit,
+    Backups { selected: usize },
+    Workspaces { selected: usize },
+    MoveTo { selected: usize },
+    Statuses { selected: usize },
+    Checklist { selected: usize },
+    Blockers { task: Id, selected: usize },
+    Links { selected: usize },
 }
 
 #[derive(Copy, Clone)]
@@ -48,27 +118,79 @@ pub enum InputRequestType {
     RenameFolder,
     NewTask { step: TaskStep },
     EditTask { step: TaskStep },
-    ConfirmDelete,
+    ConfirmDelete { count: usize },
+    ConfirmEmpty,
+    ConfirmQuit,
+    ConfirmReload,
+    Filter,
+    NewWorkspace,
+    AddChecklistItem { index: usize },
+    EditChecklistItem { index: usize },
 }
 
 impl InputRequestType {
+    // Details are typed into the multi-line editor instead of the input line
+    pub fn edits_details(&self) -> bool {
+        matches!(
+            self,
+            InputRequestType::NewTask {
+                step: TaskStep::Details
+            } | InputRequestType::EditTask {
+                step: TaskStep::Details
+            }
+        )
+    }
     pub fn to_message(&self) -> String {
         match self {
             InputRequestType::NewFolder => "Enter the name for the folder".to_string(),
             InputRequestType::RenameFolder => "Enter the new name for the folder".to_string(),
             InputRequestType::NewTask { step } => format!("New Task: {}", step.to_message()),
             InputRequestType::EditTask { step } => format!("Edit Task: {}", step.to_message()),
-            InputRequestType::ConfirmDelete => "Are you sure? Y/N".to_string(),
+            InputRequestType::ConfirmDelete { count: 1 } => "Are you sure? Y/N".to_string(),
+            InputRequestType::ConfirmDelete { count } => {
+                format!("Delete {} marked items? Y/N", count)
+            }
+            InputRequestType::ConfirmEmpty => "Permanently delete everything? Y/N".to_string(),
+            InputRequestType::ConfirmQuit => {
+                "Unsaved changes! (S)ave, (D)iscard or (C)ancel?".to_string()
+            }
+            InputRequestType::ConfirmReload => {
+                "Changed on disk too! (K)eep mine, (L)oad theirs or (M)erge both?".to_string()
+            }
+            InputRequestType::Filter => "Filter by title, details or status".to_string(),
+            InputRequestType::NewWorkspace => "Enter the name for the workspace".to_string(),
+            InputRequestType::AddChecklistItem { .. } => "Enter the checklist item".to_string(),
+            InputRequestType::EditChecklistItem { .. } => "Edit the checklist item".to_string(),
         }
     }
 }
 
+// How many rows page up and page down scroll the details by
+const DETAILS_PAGE: i16 = 5;
+
+const USAGE: &str = "Usage: rtui [--file <path>] [--workspace <name>]
+
+    --file <path>       Use the given task file, also read from RTASKS_FILE
+    --workspace <name>  Open a named workspace";
+
 fn main() -> Result<(), Box<dyn Error>> {
+    let workspace = match parse_args(env::args().skip(1)) {
+        Ok(Some((file, name))) => Workspace::resolve(file, name)?,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return Ok(());
+        }
+    };
+
     // Set up the terminal
     let mut terminal = setup_terminal()?;
 
     // Run main loop
-    match run(&mut terminal) {
+    match run(&mut terminal, workspace) {
         Ok(_) => {
             // Take down the terminal
             restore_terminal(&mut terminal)?;
@@ -85,118 +207,325 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// Read the --file and --workspace flags, None means help was asked for
+#[allow(clippy::type_complexity)]
+fn parse_args(
+    mut args: impl Iterator<Item = String>,
+) -> anyhow::Result<Option<(Option<PathBuf>, Option<String>)>> {
+    let (mut file, mut workspace) = (None, None);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--file" | "-f" => {
+                file = Some(PathBuf::from(
+                    args.next().ok_or_else(|| anyhow!("--file needs a path"))?,
+                ))
+            }
+            "--workspace" | "-w" => {
+                workspace = Some(
+                    args.next()
+                        .ok_or_else(|| anyhow!("--workspace needs a name"))?,
+                )
+            }
+            "--help" | "-h" => return Ok(None),
+            _ => return Err(anyhow!("Unknown argument {}", arg)),
+        }
+    }
+
+    Ok(Some((file, workspace)))
+}
+
+// Read every task file of a workspace, going through recovery if the main one is broken.
+// `read` reads the main file, reloads use Folder::read so they never create it.
+// Returns None if the user chose to quit instead
+fn load(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    workspace: &Workspace,
+    config: &Config,
+    message: &mut String,
+    read: fn(&Workspace) -> anyhow::Result<Folder>,
+) -> anyhow::Result<Option<(Folder, Store, Store)>> {
+    let folder = match read(workspace) {
+        Ok(folder) => folder,
+        Err(e) => match recovery::recover(terminal, workspace, e)? {
+            Some((folder, recovered)) => {
+                *message = recovered;
+                folder
+            }
+            None => return Ok(None),
+        },
+    };
+
+    let mut trash = Store::read_or_create(workspace, TaskFile::Trash)?;
+    let complete = Store::read_or_create(workspace, TaskFile::Complete)?;
+
+    if let Some(days) = config.trash_retention_days {
+        trash.purge_older_than(days);
+    }
+
+    Ok(Some((folder, trash, complete)))
+}
+
 // The main render function of the engine
-fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> anyhow::Result<()> {
+fn run(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    mut workspace: Workspace,
+) -> anyhow::Result<()> {
     let mut selected_tab = 0;
 
-    let mut selected: Vec<String> = vec![];
-    let mut folder = Folder::read_or_create()?;
+    let config = Config::read_or_create()?;
+
+    let mut message = String::new();
+
+    // The ids of the folders leading to the one being shown
+    let mut selected: Vec<Id> = vec![];
+    let Some((mut folder, mut trash, mut complete)) = load(
+        terminal,
+        &workspace,
+        &config,
+        &mut message,
+        Folder::read_or_create,
+    )?
+    else {
+        return Ok(());
+    };
+    let mut statuses = Statuses::read_or_create(&workspace)?;
 
     let mut input_status = InputStatus::Empty;
     let mut input = tui_input::Input::new("".to_string());
 
     let mut temp_task = Task::default();
+    let mut editor = TextArea::new("");
+    let mut details_scroll = Scroll::default();
 
-    // Main window loop
-    loop {
-        let cur_folder = folder.get_folder(selected.clone()).unwrap();
+    let mut calendar = Calendar::new();
+    let mut filter = Filter::new();
+    let mut tag_browser = TagBrowser::new();
+    let mut timesheet = Timesheet::new();
 
-        // Render the frame
-        terminal.draw(|frame| {
-            let chunks = make_chunks(frame);
+    let mut history = History::new(config.undo_depth);
 
-            let list = cur_folder.as_list_widget().block(
-                Block::default()
-                    .title("Tasks")
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Plain),
-            );
-            frame.render_widget(list, chunks.left_menu());
+    // Anything recorded in the history since the last save is unsaved
+    let mut saved_version = history.version();
+    let mut last_save = Instant::now();
 
-            if let Some(task) = cur_folder.get_selected_task() {
-                let border = Block::default()
-                    .title("Task Details")
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Double);
+    let mut switch_to: Option<Workspace> = None;
 
-                frame.render_widget(border, chunks.right_menu());
+    let mut clipboard: Option<Clipboard> = None;
 
-                let status = task.status.to_paragraph().block(
-                    Block::default()
-                        .title("Status")
-                        .borders(border!(TOP))
-                        .style(Style::new().fg(Color::White)),
-                );
+    // The last change to the file we know about, anything newer came from somewhere else
+    let mut modified = workspace.modified();
 
-                frame.render_widget(status, chunks.top_detail());
+    // Main window loop
+    loop {
+        // Recurring tasks that got done come back as their next instance
+        let edits = roll_over(&mut folder, &mut complete, &statuses);
+        history.amend("repeat tasks", edits);
+        folder.sort_all(&|status| statuses.rank(status));
+        // Find the shown folder again if it moved, or step back out of it if it no longer exists
+        while folder.get_folder(selected.clone()).is_err() {
+            match selected.last().and_then(|id| folder.path_to(*id)) {
+                Some(path) => selected = path,
+                None => {
+                    selected.pop();
+                }
+            }
+        }
 
-                let details = Paragraph::new(task.task.clone()).block(
-                    Block::new()
-                        .title("Details")
-                        .borders(border!(TOP))
-                        .border_type(BorderType::Plain),
-                );
+        // Pick up changes made by someone else, like a sync tool or another machine.
+        // While the file is missing, as when it is being replaced, wait for it to come back
+        let changed = workspace
+            .modified()
+            .is_some_and(|now| Some(now) != modified);
+        if matches!(input_status, InputStatus::Empty) && changed {
+            if history.version() == saved_version {
+                match load(terminal, &workspace, &config, &mut message, Folder::read)? {
+                    Some(loaded) => {
+                        (folder, trash, complete) = loaded;
+                        history.clear();
+                        (saved_version, modified) = (history.version(), workspace.modified());
+                        message = "Reloaded, the file was changed on disk".to_string();
+                        continue;
+                    }
+                    None => break,
+                }
+            } else {
+                input_status = InputStatus::Request(InputRequestType::ConfirmReload);
+            }
+        }
 
-                frame.render_widget(details, chunks.detail());
+        let dirty = history.version() != saved_version;
+        let mut title = match dirty {
+            true => format!("R-Tasks - {} [+]", workspace.name),
+            false => format!("R-Tasks - {}", workspace.name),
+        };
+        if let Some(found) = folder.running_task() {
+            let started = found.task.running().unwrap_or_default();
+            title.push_str(&format!(
+                "  ⏱ {} {}",
+                found.task.title,
+                format_duration(Local::now().naive_local() - started)
+            ));
+        }
 
-                let misc = Paragraph::new("").block(
-                    Block::new()
-                        .title("Misc")
-                        .borders(border!(TOP))
-                        .border_type(BorderType::Plain),
-                );
+        // Render the frame
+        terminal.draw(|frame| {
+            let chunks = make_chunks(frame);
 
-                frame.render_widget(misc, chunks.bottom_detail());
-            } else if let Some(folder) = cur_folder.get_selected_folder() {
-                let details = folder.as_list_widget().block(
-                    Block::default()
-                        .title("Inner Tasks")
-                        .borders(Borders::ALL)
-                        .border_type(BorderType::Double),
-                );
+            match selected_tab {
+                1 => calendar.render(frame, &chunks, &folder),
+                2 => {
+                    // While the query is being typed, filter live on the input
+                    let query = match input_status {
+                        InputStatus::Request(InputRequestType::Filter) => input.value().to_string(),
+                        _ => filter.query.clone(),
+                    };
+                    filter.render(frame, &chunks, &folder, &query, &mut details_scroll)
+                }
+                3 => tag_browser.render(frame, &chunks, &folder, &mut details_scroll),
+                4 => timesheet.render(frame, &chunks, &folder, &complete),
+                5 => trash.render(frame, &chunks, "Trash", &mut details_scroll),
+                6 => complete.render(frame, &chunks, "Complete", &mut details_scroll),
+                _ => {
+                    // Keep the checklist highlighted while one of its items is being typed
+                    let checklist = match input_status {
+                        InputStatus::Checklist { selected } => Some(selected),
+                        InputStatus::Request(
+                            InputRequestType::AddChecklistItem { index }
+                            | InputRequestType::EditChecklistItem { index },
+                        ) => Some(index),
+                        _ => None,
+                    };
 
-                frame.render_widget(details, chunks.right_menu());
+                    let link = match input_status {
+                        InputStatus::Links { selected } => Some(selected),
+                        _ => None,
+                    };
+                    let is_done = |status: &Status| statuses.is_done(status);
+                    let blocked = deps::blocked(&folder, is_done);
+                    let links = match folder.get_folder(selected.clone()) {
+                        Ok(cur_folder) => match cur_folder.selected_id() {
+                            Some(id) => deps::links(&folder, id, is_done),
+                            None => vec![],
+                        },
+                        Err(_) => vec![],
+                    };
+
+                    if let Ok(cur_folder) = folder.get_folder(selected.clone()) {
+                        render_list(
+                            frame,
+                            &chunks,
+                            cur_folder,
+                            &blocked,
+                            &is_done,
+                            checklist,
+                            (&links, link),
+                            &mut details_scroll,
+                        )
+                    }
+                }
             }
 
             // Render extra
-            render_tabs(
-                frame,
-                &chunks,
-                selected_tab,
-                vec!["[TAB]  List", "Calendar", "Filter"],
+            render_tabs(frame, &chunks, title, selected_tab, TABS.to_vec());
+            // While tags are typed, the status line suggests the ones already in use
+            let status_line = match input_status {
+                InputStatus::Request(InputRequestType::EditTask {
+                    step: TaskStep::Tags,
+                }) => tags::suggestions(&folder, input.value()).join("  "),
+                _ => message.clone(),
+            };
+            frame.render_widget(
+                Paragraph::new(status_line).style(Style::default().fg(Color::LightYellow)),
+                chunks.status_line(),
             );
 
+            // Clear whatever is under the popups
+            if let InputStatus::Request(request) = input_status {
+                if !request.edits_details() {
+                    frame.render_widget(Clear, chunks.input_popup())
+                }
+            }
+
             match input_status {
-                InputStatus::Controls => render_help(frame, &chunks),
-                InputStatus::New => frame.render_widget(
-                    Paragraph::new(vec![Line::from(" <t> TASK "), Line::from(" <f> FOLDER ")])
-                        .block(
-                            Block::default()
-                                .title("Help")
-                                .borders(Borders::ALL)
-                                .style(Style::new().fg(Color::LightCyan)),
-                        ),
-                    chunks.message_popup(),
+                InputStatus::Controls => render_help(frame, &chunks, selected_tab == 0),
+                InputStatus::Workspaces { selected } => render_picker(
+                    frame,
+                    &chunks,
+                    "Workspaces  <enter> SWITCH  <n> NEW  <esc> CANCEL",
+                    workspace::picker_items(&workspace.name),
+                    selected,
+                ),
+                InputStatus::Backups { selected } => render_picker(
+                    frame,
+                    &chunks,
+                    "Restore Backup  <enter> RESTORE  <esc> CANCEL",
+                    backup::picker_items(&workspace),
+                    selected,
                 ),
-                InputStatus::Edit => frame.render_widget(
-                    Paragraph::new(vec![
+                InputStatus::MoveTo { selected } => render_picker(
+                    frame,
+                    &chunks,
+                    "Move To  <enter> MOVE  <esc> CANCEL",
+                    clipboard::picker_items(&folder),
+                    selected,
+                ),
+                InputStatus::Blockers { task, selected } => render_picker(
+                    frame,
+                    &chunks,
+                    "Blocked By  <enter> TOGGLE  <esc> DONE",
+                    deps::picker_items(&folder, task),
+                    selected,
+                ),
+                InputStatus::Statuses { selected: index } => {
+                    if let Ok(cur_folder) = folder.get_folder(selected.clone()) {
+                        let options = status_options(&statuses, cur_folder);
+                        render_picker(
+                            frame,
+                            &chunks,
+                            "Status  <enter> SET  <esc> CANCEL",
+                            status::picker_items(&options),
+                            index,
+                        )
+                    }
+                }
+                InputStatus::New => render_menu(
+                    frame,
+                    &chunks,
+                    "Help",
+                    vec![Line::from(" <t> TASK "), Line::from(" <f> FOLDER ")],
+                ),
+                InputStatus::Edit => render_menu(
+                    frame,
+                    &chunks,
+                    "Help",
+                    vec![
                         Line::from(" <d> DETAILS "),
+                        Line::from(" <x> DETAILS IN $EDITOR "),
                         Line::from(" <n> NAME "),
                         Line::from(" <s> STATUS "),
-                    ])
-                    .block(
-                        Block::default()
-                            .title("Help")
-                            .borders(Borders::ALL)
-                            .style(Style::new().fg(Color::LightCyan)),
-                    ),
-                    chunks.message_popup(),
+                        Line::from(" <u> DUE "),
+                        Line::from(" <b> START "),
+                        Line::from(" <g> TAGS "),
+                        Line::from(" <k> BLOCKED BY "),
+                        Line::from(" <r> REPEAT "),
+                        Line::from(" <e> ESTIMATE "),
+                    ],
                 ),
                 _ => {}
             }
 
             // Finally if input is active, render it.
             if let InputStatus::Request(event) = input_status {
+                if event.edits_details() {
+                    editor.render(
+                        frame,
+                        chunks.right_menu(),
+                        &format!("{}  <ctrl-s> SAVE  <esc> CANCEL", event.to_message()),
+                    );
+                    return;
+                }
+
                 let popup = Paragraph::new(input.value()).block(
                     Block::default()
                         .title(event.to_message())
@@ -212,6 +541,12 @@ fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> anyhow::Result<()>
         if event::poll(Duration::from_millis(1500))? {
             if let Event::Key(key_event) = event::read()? {
                 let key = key_event.code;
+                let path = folder.path_names(&selected);
+                let cur_folder = folder.get_folder(selected.clone())?;
+                message.clear();
+                // The details editor keeps enter for new lines and commits with ctrl-s instead
+                let submit = key_event.modifiers.contains(KeyModifiers::CONTROL)
+                    && key == KeyCode::Char('s');
 
                 match input_status {
                     InputStatus::Empty => {
@@ -219,38 +554,294 @@ fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> anyhow::Result<()>
                             input_status = InputStatus::Controls;
                         }
                         match key {
+                            KeyCode::Tab => {
+                                selected_tab += 1;
+                                selected_tab %= TABS.len();
+
+                                if selected_tab == 2 && filter.query.is_empty() {
+                                    input_status = InputStatus::Request(InputRequestType::Filter)
+                                }
+                            }
+                            KeyCode::Char('r')
+                                if key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+                            {
+                                message = match history.redo(&mut folder, &mut trash, &mut complete)
+                                {
+                                    Some(description) => format!("Redid {}", description),
+                                    None => "Nothing to redo".to_string(),
+                                }
+                            }
+                            KeyCode::Char('u') => {
+                                message = match history.undo(&mut folder, &mut trash, &mut complete)
+                                {
+                                    Some(description) => format!("Undid {}", description),
+                                    None => "Nothing to undo".to_string(),
+                                }
+                            }
+                            _ if selected_tab == 1 => calendar.handle_key(key),
+                            // The other tabs that show a task scroll its details the same way
+                            KeyCode::PageDown if selected_tab != 4 => {
+                                details_scroll.by(DETAILS_PAGE)
+                            }
+                            KeyCode::PageUp if selected_tab != 4 => {
+                                details_scroll.by(-DETAILS_PAGE)
+                            }
+                            KeyCode::Char('/') if selected_tab == 2 => {
+                                input = input.with_value(filter.query.clone());
+                                input_status = InputStatus::Request(InputRequestType::Filter)
+                            }
+                            KeyCode::Enter if selected_tab == 2 => {
+                                if let Some((path, id)) = filter.selected_result(&folder) {
+                                    folder.get_folder(path.clone())?.select(id);
+                                    selected = path;
+                                    selected_tab = 0;
+                                }
+                            }
+                            _ if selected_tab == 2 => filter.handle_key(key),
+                            KeyCode::Enter if selected_tab == 3 => {
+                                if let Some((path, id)) = tag_browser.selected_task(&folder) {
+                                    folder.get_folder(path.clone())?.select(id);
+                                    selected = path;
+                                    selected_tab = 0;
+                                }
+                            }
+                            _ if selected_tab == 3 => tag_browser.handle_key(key),
+                            _ if selected_tab == 4 => timesheet.handle_key(key),
+                            _ if selected_tab >= 5 => {
+                                let store = match selected_tab {
+                                    5 => &mut trash,
+                                    _ => &mut complete,
+                                };
+
+                                match key {
+                                    KeyCode::Down => store.adjust_selected(1),
+                                    KeyCode::Up => store.adjust_selected(-1),
+                                    KeyCode::Char('r') => {
+                                        store.restore_selected(&mut folder, &mut history)
+                                    }
+                                    KeyCode::Char('E') => {
+                                        input_status =
+                                            InputStatus::Request(InputRequestType::ConfirmEmpty)
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            KeyCode::Up | KeyCode::Down
+                                if key_event.modifiers.contains(KeyModifiers::SHIFT)
+                                    && cur_folder.sort != SortMode::Manual =>
+                            {
+                                message = "Switch to manual sorting with <S> to reorder".to_string()
+                            }
+                            KeyCode::Up | KeyCode::Down
+                                if key_event.modifiers.contains(KeyModifiers::SHIFT) =>
+                            {
+                                let dist = match key {
+                                    KeyCode::Up => -1,
+                                    _ => 1,
+                                };
+                                if let (Some(item), Some(index)) =
+                                    (cur_folder.selected_item(), cur_folder.reorder_target(dist))
+                                {
+                                    let to = cur_folder.id;
+                                    move_items(
+                                        &mut folder,
+                                        &mut history,
+                                        &[item.id()],
+                                        to,
+                                        Some(index),
+                                    );
+                                }
+                            }
                             KeyCode::Down => cur_folder.adjust_selected(1),
                             KeyCode::Up => cur_folder.adjust_selected(-1),
+
+                            KeyCode::Char('s') => {
+                                message =
+                                    set_status(cur_folder, &mut history, &statuses, |status| {
+                                        statuses.cycle(status)
+                                    })
+                            }
+                            KeyCode::Char('S') => {
+                                let (from, to) = (cur_folder.sort, cur_folder.sort.next());
+                                cur_folder.sort = to;
+                                history.record(
+                                    format!("sort {} by {}", cur_folder.name, to.name()),
+                                    vec![Edit::Sort {
+                                        id: cur_folder.id,
+                                        from,
+                                        to,
+                                    }],
+                                );
+                                message = format!("Sorting by {}", to.name());
+                            }
+                            KeyCode::Char(c) if Priority::from_key(c).is_some() => {
+                                if let Some(priority) = Priority::from_key(c) {
+                                    message =
+                                        set_priority(cur_folder, &mut history, &statuses, priority);
+                                }
+                            }
+                            KeyCode::Char('v') => {
+                                cur_folder.toggle_mark();
+                                cur_folder.adjust_selected(1);
+                            }
+                            KeyCode::Char('V') => cur_folder.toggle_mark_all(),
+                            KeyCode::Esc => cur_folder.clear_marks(),
+                            KeyCode::Char('x') => {
+                                if let Some(item) = cur_folder.selected_item() {
+                                    message = format!("Cut {}, paste it with <p>", item.name());
+                                    clipboard = Some(Clipboard::Cut(item.id()));
+                                }
+                            }
+                            KeyCode::Char('y') => {
+                                if let Some(item) = cur_folder.selected_item() {
+                                    message = format!("Copied {}, paste it with <p>", item.name());
+                                    clipboard = Some(Clipboard::Copy(Box::new(item)));
+                                }
+                            }
+                            KeyCode::Char('p') => match &clipboard {
+                                Some(Clipboard::Cut(id)) => {
+                                    let (id, to) = (*id, cur_folder.id);
+                                    message =
+                                        move_items(&mut folder, &mut history, &[id], to, None);
+                                    clipboard = None;
+                                }
+                                Some(Clipboard::Copy(item)) => {
+                                    let item = item.duplicate();
+                                    let index = cur_folder.insert(item.clone());
+                                    cur_folder.select(item.id());
+                                    history.record(
+                                        format!("paste {}", item.name()),
+                                        vec![Edit::Insert {
+                                            parent: cur_folder.id,
+                                            index,
+                                            item,
+                                        }],
+                                    );
+                                }
+                                None => message = "Nothing to paste".to_string(),
+                            },
                             KeyCode::Right => {
                                 if let Some(subfolder) = cur_folder.get_selected_folder() {
-                                    selected.push(subfolder.name.clone());
+                                    selected.push(subfolder.id);
+                                } else if cur_folder.get_selected_task().is_some() {
+                                    input_status = InputStatus::Checklist { selected: 0 };
                                 }
                             }
                             KeyCode::Left => {
                                 selected.pop();
                             }
-                            KeyCode::Tab => {
-                                selected_tab += 1;
-                                selected_tab %= 3;
+                            KeyCode::Char('t') => {
+                                if let Some(id) = cur_folder.get_selected_task().map(|task| task.id)
+                                {
+                                    message = toggle_timer(&mut folder, &mut history, id);
+                                }
+                            }
+                            KeyCode::Char('j') => {
+                                let has_links = match cur_folder.get_selected_task() {
+                                    Some(task) => {
+                                        let id = task.id;
+                                        !deps::links(&folder, id, |_| false).is_empty()
+                                    }
+                                    None => false,
+                                };
+                                match has_links {
+                                    true => input_status = InputStatus::Links { selected: 0 },
+                                    false => message = "No dependencies to jump to".to_string(),
+                                }
                             }
                             _ => {}
                         }
                     }
                     InputStatus::Controls => match key {
-                        KeyCode::Char('q') => break,
-                        KeyCode::Char('n') => input_status = InputStatus::New,
-                        KeyCode::Char('e') => match cur_folder.get_selected_folder() {
-                            Some(_) => {
-                                input_status = InputStatus::Request(InputRequestType::RenameFolder)
+                        // The other tabs show other tasks than the one selected in the list
+                        KeyCode::Char('n' | 'e' | 'd' | 'c' | 'm') if selected_tab != 0 => {
+                            message = "Switch to the List tab to change tasks".to_string();
+                            input_status = InputStatus::Empty
+                        }
+                        KeyCode::Char('q') => match dirty {
+                            true => {
+                                input_status = InputStatus::Request(InputRequestType::ConfirmQuit)
                             }
-                            None => input_status = InputStatus::Edit,
+                            false => break,
                         },
+                        KeyCode::Char('n') => input_status = InputStatus::New,
+                        // With marks, the edit goes to the marked tasks even if a folder is selected
+                        KeyCode::Char('e') => {
+                            input_status = match cur_folder.marked().is_empty()
+                                && cur_folder.get_selected_folder().is_some()
+                            {
+                                true => InputStatus::Request(InputRequestType::RenameFolder),
+                                false => InputStatus::Edit,
+                            }
+                        }
                         KeyCode::Char('w') => {
-                            folder.save()?;
+                            message = match save(&workspace, &folder, &trash, &complete, &config) {
+                                Ok(warning) => {
+                                    (saved_version, last_save) =
+                                        (history.version(), Instant::now());
+                                    modified = workspace.modified();
+                                    warning.unwrap_or_else(|| "Saved".to_string())
+                                }
+                                Err(e) => format!("Save failed: {}", e),
+                            };
                             input_status = InputStatus::Empty
                         }
-                        KeyCode::Char('d') => {
-                            input_status = InputStatus::Request(InputRequestType::ConfirmDelete)
+                        KeyCode::Char('d') => match cur_folder.marked_or_selected().len() {
+                            0 => input_status = InputStatus::Empty,
+                            count => {
+                                input_status =
+                                    InputStatus::Request(InputRequestType::ConfirmDelete { count })
+                            }
+                        },
+                        KeyCode::Char('b') => input_status = InputStatus::Backups { selected: 0 },
+                        KeyCode::Char('o') => {
+                            input_status = InputStatus::Workspaces { selected: 0 }
+                        }
+                        KeyCode::Char('m') => match cur_folder.marked_or_selected().is_empty() {
+                            false => input_status = InputStatus::MoveTo { selected: 0 },
+                            true => input_status = InputStatus::Empty,
+                        },
+                        KeyCode::Char('c') => {
+                            if let Some(from) = cur_folder.get_selected_task().cloned() {
+                                let index = cur_folder.position(from.id).unwrap_or_default();
+                                let mut to = from.clone();
+                                to.status = statuses.done();
+                                to.stop_timer(Local::now().naive_local());
+                                to.record_changes(&from, true);
+                                // The finished instance stays finished, the next one repeats
+                                let next = recur::next_instance(&to, statuses.initial());
+                                to.recur = None;
+
+                                cur_folder.delete_selected();
+                                complete.push(cur_folder.id, path.clone(), Item::Task(to.clone()));
+                                history.record(
+                                    format!("complete {}", to.title),
+                                    vec![
+                                        Edit::task(from, to.clone()),
+                                        Edit::Delete {
+                                            parent: cur_folder.id,
+                                            path,
+                                            index,
+                                            item: Item::Task(to),
+                                            file: TaskFile::Complete,
+                                        },
+                                    ],
+                                );
+
+                                if let Some(next) = next {
+                                    let item = Item::Task(next);
+                                    cur_folder.insert_at(index, item.clone());
+                                    history.amend(
+                                        "repeat tasks",
+                                        vec![Edit::Insert {
+                                            parent: cur_folder.id,
+                                            index,
+                                            item,
+                                        }],
+                                    );
+                                }
+                            }
+                            input_status = InputStatus::Empty
                         }
                         _ => input_status = InputStatus::Empty,
                     },
@@ -272,37 +863,394 @@ fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> anyhow::Result<()>
                             });
                         }
                         KeyCode::Char('d') => {
+                            let details = match cur_folder.marked_or_selected().as_slice() {
+                                [id] => cur_folder.find_task(*id).map(|task| task.task.clone()),
+                                _ => None,
+                            };
+                            editor = TextArea::new(&details.unwrap_or_default());
                             input_status = InputStatus::Request(InputRequestType::EditTask {
                                 step: TaskStep::Details,
                             });
                         }
-                        KeyCode::Char('s') => {
+                        KeyCode::Char('x') => {
+                            if let [id] = cur_folder.marked_or_selected().as_slice() {
+                                if let Some(from) = cur_folder.find_task(*id).cloned() {
+                                    match external::edit_task(terminal, &from, &statuses) {
+                                        Ok(mut to) if to != from => {
+                                            to.record_changes(&from, statuses.is_done(&to.status));
+                                            if let Some(task) = cur_folder.find_task(*id) {
+                                                *task = to.clone();
+                                            }
+                                            history.record(
+                                                format!("edit {}", to.title),
+                                                vec![Edit::task(from, to)],
+                                            );
+                                        }
+                                        Ok(_) => {}
+                                        Err(e) => message = e.to_string(),
+                                    }
+                                }
+                            }
+                            input_status = InputStatus::Empty
+                        }
+                        KeyCode::Char('s') => input_status = InputStatus::Statuses { selected: 0 },
+                        KeyCode::Char('u') => {
+                            input_status = InputStatus::Request(InputRequestType::EditTask {
+                                step: TaskStep::Due,
+                            })
+                        }
+                        KeyCode::Char('b') => {
                             input_status = InputStatus::Request(InputRequestType::EditTask {
-                                step: TaskStep::Status,
+                                step: TaskStep::Start,
                             })
                         }
+                        KeyCode::Char('g') => {
+                            // Several marked tasks get tags added, a single one has them edited
+                            if let [id] = cur_folder.marked_or_selected().as_slice() {
+                                if let Some(task) = cur_folder.find_task(*id) {
+                                    let current = task.tags.iter().cloned().collect::<Vec<_>>();
+                                    input = input.with_value(current.join(" "));
+                                }
+                            }
+                            input_status = InputStatus::Request(InputRequestType::EditTask {
+                                step: TaskStep::Tags,
+                            })
+                        }
+                        KeyCode::Char('e') => {
+                            if let [id] = cur_folder.marked_or_selected().as_slice() {
+                                if let Some(estimate) =
+                                    cur_folder.find_task(*id).and_then(|task| task.estimate())
+                                {
+                                    input = input.with_value(format_duration(estimate));
+                                }
+                            }
+                            input_status = InputStatus::Request(InputRequestType::EditTask {
+                                step: TaskStep::Estimate,
+                            })
+                        }
+                        KeyCode::Char('r') => {
+                            if let [id] = cur_folder.marked_or_selected().as_slice() {
+                                if let Some(rule) = cur_folder
+                                    .find_task(*id)
+                                    .and_then(|task| task.recur.as_ref())
+                                {
+                                    input = input.with_value(rule.to_string());
+                                }
+                            }
+                            input_status = InputStatus::Request(InputRequestType::EditTask {
+                                step: TaskStep::Repeat,
+                            })
+                        }
+                        KeyCode::Char('k') => match cur_folder.get_selected_task() {
+                            Some(task) => {
+                                input_status = InputStatus::Blockers {
+                                    task: task.id,
+                                    selected: 0,
+                                }
+                            }
+                            None => input_status = InputStatus::Empty,
+                        },
+                        _ => input_status = InputStatus::Empty,
+                    },
+                    InputStatus::Workspaces { selected: index } => match key {
+                        KeyCode::Up | KeyCode::Down => {
+                            let len = Workspace::list()?.len();
+                            input_status = InputStatus::Workspaces {
+                                selected: move_selection(key, index, len),
+                            }
+                        }
+                        KeyCode::Enter => {
+                            if let Some(name) = Workspace::list()?.get(index) {
+                                switch_to = Some(Workspace::named(name)?);
+                            }
+                            input_status = InputStatus::Empty
+                        }
+                        KeyCode::Char('n') => {
+                            input_status = InputStatus::Request(InputRequestType::NewWorkspace)
+                        }
+                        _ => input_status = InputStatus::Empty,
+                    },
+                    InputStatus::Checklist { selected: index } => {
+                        let len = match cur_folder.get_selected_task() {
+                            Some(task) => task.checklist.len(),
+                            None => {
+                                input_status = InputStatus::Empty;
+                                continue;
+                            }
+                        };
+                        let shift = key_event.modifiers.contains(KeyModifiers::SHIFT);
+
+                        match key {
+                            KeyCode::Esc | KeyCode::Left => input_status = InputStatus::Empty,
+                            KeyCode::Up if shift && index > 0 && index < len => {
+                                change_checklist(
+                                    cur_folder,
+                                    &mut history,
+                                    &statuses,
+                                    "reorder",
+                                    |task| task.checklist.swap(index, index - 1),
+                                );
+                                input_status = InputStatus::Checklist {
+                                    selected: index - 1,
+                                }
+                            }
+                            KeyCode::Down if shift && index + 1 < len => {
+                                change_checklist(
+                                    cur_folder,
+                                    &mut history,
+                                    &statuses,
+                                    "reorder",
+                                    |task| task.checklist.swap(index, index + 1),
+                                );
+                                input_status = InputStatus::Checklist {
+                                    selected: index + 1,
+                                }
+                            }
+                            KeyCode::Up | KeyCode::Down => {
+                                input_status = InputStatus::Checklist {
+                                    selected: move_selection(key, index, len),
+                                }
+                            }
+                            KeyCode::Char(' ') | KeyCode::Enter => {
+                                let auto_complete = config.auto_complete_checklists;
+                                let done = statuses.done();
+                                change_checklist(
+                                    cur_folder,
+                                    &mut history,
+                                    &statuses,
+                                    "check",
+                                    |task| {
+                                        if let Some(item) = task.checklist.get_mut(index) {
+                                            item.done = !item.done;
+                                        }
+                                        if auto_complete
+                                            && task.checklist.iter().all(|item| item.done)
+                                        {
+                                            task.status = done;
+                                        }
+                                    },
+                                );
+                            }
+                            KeyCode::Char('a') => {
+                                input_status =
+                                    InputStatus::Request(InputRequestType::AddChecklistItem {
+                                        index,
+                                    })
+                            }
+                            KeyCode::Char('e') => {
+                                if let Some(item) = cur_folder
+                                    .get_selected_task()
+                                    .and_then(|task| task.checklist.get(index))
+                                {
+                                    input = input.with_value(item.text.clone());
+                                    input_status =
+                                        InputStatus::Request(InputRequestType::EditChecklistItem {
+                                            index,
+                                        })
+                                }
+                            }
+                            KeyCode::Char('d') if index < len => {
+                                change_checklist(
+                                    cur_folder,
+                                    &mut history,
+                                    &statuses,
+                                    "delete",
+                                    |task| {
+                                        task.checklist.remove(index);
+                                    },
+                                );
+                                input_status = InputStatus::Checklist {
+                                    selected: index.min(len.saturating_sub(2)),
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    InputStatus::Statuses { selected: index } => match key {
+                        KeyCode::Up | KeyCode::Down => {
+                            let len = status_options(&statuses, cur_folder).len();
+                            input_status = InputStatus::Statuses {
+                                selected: move_selection(key, index, len),
+                            }
+                        }
+                        KeyCode::Enter => {
+                            let picked = status_options(&statuses, cur_folder).get(index).copied();
+                            if let Some(def) = picked {
+                                // Tasks whose status can't go to the picked one keep theirs
+                                let refused = cur_folder
+                                    .marked_or_selected()
+                                    .into_iter()
+                                    .filter(|id| {
+                                        cur_folder
+                                            .find_task(*id)
+                                            .is_some_and(|task| !statuses.can_go(&task.status, def))
+                                    })
+                                    .count();
+                                let to = def.to_status();
+                                message = set_status(cur_folder, &mut history, &statuses, |from| {
+                                    statuses.can_go(from, def).then(|| to.clone())
+                                });
+                                if refused > 0 {
+                                    message = format!(
+                                        "{}, {} can't go to {}",
+                                        message, refused, to.status
+                                    );
+                                }
+                            }
+                            input_status = InputStatus::Empty
+                        }
+                        _ => input_status = InputStatus::Empty,
+                    },
+                    InputStatus::MoveTo { selected: index } => match key {
+                        KeyCode::Up | KeyCode::Down => {
+                            let len = folder.all_folders().len();
+                            input_status = InputStatus::MoveTo {
+                                selected: move_selection(key, index, len),
+                            }
+                        }
+                        KeyCode::Enter => {
+                            let ids = cur_folder.marked_or_selected();
+                            cur_folder.clear_marks();
+                            if let Some((to, _)) = folder.all_folders().get(index) {
+                                message = move_items(&mut folder, &mut history, &ids, *to, None);
+                            }
+                            input_status = InputStatus::Empty
+                        }
+                        _ => input_status = InputStatus::Empty,
+                    },
+                    InputStatus::Blockers {
+                        task,
+                        selected: index,
+                    } => match key {
+                        KeyCode::Up | KeyCode::Down => {
+                            let len = deps::options(&folder, task).len();
+                            input_status = InputStatus::Blockers {
+                                task,
+                                selected: move_selection(key, index, len),
+                            }
+                        }
+                        KeyCode::Enter | KeyCode::Char(' ') => {
+                            let picked = deps::options(&folder, task)
+                                .get(index)
+                                .map(|found| (found.task.id, found.full_path()));
+                            if let Some((blocker, name)) = picked {
+                                message =
+                                    toggle_blocker(&mut folder, &mut history, task, blocker, &name);
+                            }
+                        }
+                        _ => input_status = InputStatus::Empty,
+                    },
+                    InputStatus::Links { selected: index } => {
+                        let links = match cur_folder.get_selected_task() {
+                            Some(task) => {
+                                let id = task.id;
+                                deps::links(&folder, id, |_| false)
+                            }
+                            None => vec![],
+                        };
+
+                        match key {
+                            KeyCode::Up | KeyCode::Down => {
+                                input_status = InputStatus::Links {
+                                    selected: move_selection(key, index, links.len()),
+                                }
+                            }
+                            KeyCode::Enter | KeyCode::Right => {
+                                if let Some(link) = links.get(index) {
+                                    folder.get_folder(link.folders.clone())?.select(link.id);
+                                    selected = link.folders.clone();
+                                }
+                                input_status = InputStatus::Empty
+                            }
+                            _ => input_status = InputStatus::Empty,
+                        }
+                    }
+                    InputStatus::Backups { selected: index } => match key {
+                        KeyCode::Up | KeyCode::Down => {
+                            let len = backup::list(&workspace)?.len();
+                            input_status = InputStatus::Backups {
+                                selected: move_selection(key, index, len),
+                            }
+                        }
+                        KeyCode::Enter => {
+                            if let Some(path) = backup::list(&workspace)?.get(index) {
+                                let restored = fs::read_to_string(path)
+                                    .map_err(anyhow::Error::from)
+                                    .and_then(|data| format::parse(&data));
+
+                                message = match restored {
+                                    Ok(restored) => {
+                                        folder = restored;
+                                        selected.clear();
+                                        history.clear();
+                                        format!(
+                                            "Restored {}, save to keep it",
+                                            path.file_name().unwrap_or_default().to_string_lossy()
+                                        )
+                                    }
+                                    Err(e) => format!("Failed to restore backup: {}", e),
+                                };
+                            }
+                            input_status = InputStatus::Empty
+                        }
                         _ => input_status = InputStatus::Empty,
                     },
                     InputStatus::Request(request) => match key {
+                        _ if request.edits_details() && !submit && key != KeyCode::Esc => {
+                            editor.handle_key(key_event)
+                        }
                         KeyCode::Esc => {
                             input = input.with_value("".to_string());
-                            input_status = InputStatus::Empty;
+                            input_status = match request {
+                                InputRequestType::AddChecklistItem { index }
+                                | InputRequestType::EditChecklistItem { index } => {
+                                    InputStatus::Checklist { selected: index }
+                                }
+                                _ => InputStatus::Empty,
+                            };
                         }
-                        KeyCode::Enter => {
+                        _ if key == KeyCode::Enter || submit => {
+                            if request.edits_details() {
+                                input = input.with_value(editor.text());
+                            }
+
                             match request {
                                 InputRequestType::NewFolder => {
-                                    cur_folder.new_folder(input.value().to_string());
+                                    let mut new_folder = Folder::new();
+                                    new_folder.name = input.value().to_string();
+
+                                    let item = Item::Folder(new_folder);
+                                    let index = cur_folder.insert(item.clone());
+                                    history.record(
+                                        format!("new folder {}", item.name()),
+                                        vec![Edit::Insert {
+                                            parent: cur_folder.id,
+                                            index,
+                                            item,
+                                        }],
+                                    );
                                     input_status = InputStatus::Empty
                                 }
                                 InputRequestType::RenameFolder => {
                                     if let Some(folder) = cur_folder.get_selected_folder() {
-                                        folder.name = input.value().to_string()
+                                        let from = folder.name.clone();
+                                        folder.name = input.value().to_string();
+
+                                        history.record(
+                                            format!("rename {} to {}", from, folder.name),
+                                            vec![Edit::Rename {
+                                                id: folder.id,
+                                                from,
+                                                to: folder.name.clone(),
+                                            }],
+                                        );
                                     }
                                     input_status = InputStatus::Empty
                                 }
                                 InputRequestType::NewTask { step } => match step {
                                     TaskStep::Title => {
                                         temp_task.title = input.value().to_string();
+                                        editor = TextArea::new("");
                                         input_status =
                                             InputStatus::Request(InputRequestType::NewTask {
                                                 step: TaskStep::Details,
@@ -310,54 +1258,301 @@ fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> anyhow::Result<()>
                                     }
                                     TaskStep::Details => {
                                         temp_task.task = input.value().to_string();
-                                        cur_folder.new_task(temp_task.clone());
+
+                                        // Taking it leaves a fresh task, with a new id, for the next one
+                                        let mut task = std::mem::take(&mut temp_task);
+                                        task.status = statuses.initial();
+                                        task.created = Some(Local::now().naive_local());
+                                        let item = Item::Task(task);
+                                        let index = cur_folder.insert(item.clone());
+                                        history.record(
+                                            format!("new task {}", item.name()),
+                                            vec![Edit::Insert {
+                                                parent: cur_folder.id,
+                                                index,
+                                                item,
+                                            }],
+                                        );
                                         input_status = InputStatus::Empty
                                     }
                                     _ => {}
                                 },
-                                InputRequestType::EditTask { step } => match step {
-                                    TaskStep::Title => {
-                                        if let Some(cur_task) = cur_folder.get_selected_task() {
-                                            cur_task.title = input.value().to_string();
+                                InputRequestType::EditTask { step } => {
+                                    // Edits apply to every marked task, or else the selected one
+                                    let targets = cur_folder.marked_or_selected();
+                                    let bulk = targets.len() > 1;
+                                    let mut edits = vec![];
+                                    let mut names = vec![];
+                                    for id in targets {
+                                        let Some(cur_task) = cur_folder.find_task(id) else {
+                                            continue;
+                                        };
+                                        let from = cur_task.clone();
+
+                                        match step {
+                                            TaskStep::Title => {
+                                                cur_task.title = input.value().to_string()
+                                            }
+                                            TaskStep::Details => {
+                                                cur_task.task = input.value().to_string()
+                                            }
+
+                                            TaskStep::Due => {
+                                                if input.value().is_empty() {
+                                                    (cur_task.due, cur_task.due_time) =
+                                                        (None, None);
+                                                } else if let Some((date, time)) =
+                                                    parse_when(input.value())
+                                                {
+                                                    (cur_task.due, cur_task.due_time) =
+                                                        (Some(date), time);
+                                                } else {
+                                                    message = format!(
+                                                        "Couldn't read {} as a due date",
+                                                        input.value()
+                                                    );
+                                                }
+                                            }
+                                            TaskStep::Estimate => {
+                                                if input.value().trim().is_empty() {
+                                                    cur_task.estimate = None;
+                                                } else if let Some(estimate) = parse_duration(
+                                                    input.value(),
+                                                )
+                                                .and_then(|estimate| {
+                                                    u32::try_from(estimate.num_minutes()).ok()
+                                                }) {
+                                                    cur_task.estimate = Some(estimate);
+                                                } else {
+                                                    message = format!(
+                                                        "Couldn't read {} as an estimate",
+                                                        input.value()
+                                                    );
+                                                }
+                                            }
+                                            TaskStep::Repeat => {
+                                                let from = cur_task
+                                                    .due
+                                                    .unwrap_or(Local::now().date_naive());
+                                                if input.value().trim().is_empty() {
+                                                    cur_task.recur = None;
+                                                } else if let Some(rule) =
+                                                    Recurrence::parse(input.value(), from)
+                                                {
+                                                    cur_task.recur = Some(rule);
+                                                } else {
+                                                    message = format!(
+                                                        "Couldn't read {} as a repeat rule",
+                                                        input.value()
+                                                    );
+                                                }
+                                            }
+                                            TaskStep::Tags => match bulk {
+                                                true => {
+                                                    cur_task.tags.extend(tags::parse(input.value()))
+                                                }
+                                                false => cur_task.tags = tags::parse(input.value()),
+                                            },
+                                            TaskStep::Start => {
+                                                if input.value().is_empty() {
+                                                    (cur_task.start, cur_task.start_time) =
+                                                        (None, None);
+                                                } else if let Some((date, time)) =
+                                                    parse_when(input.value())
+                                                {
+                                                    (cur_task.start, cur_task.start_time) =
+                                                        (Some(date), time);
+                                                } else {
+                                                    message = format!(
+                                                        "Couldn't read {} as a start date",
+                                                        input.value()
+                                                    );
+                                                }
+                                            }
+                                        }
+
+                                        if *cur_task != from {
+                                            cur_task.record_changes(
+                                                &from,
+                                                statuses.is_done(&cur_task.status),
+                                            );
+                                            names.push(cur_task.title.clone());
+                                            edits.push(Edit::task(from, cur_task.clone()));
                                         }
-                                        input_status = InputStatus::Empty
                                     }
-                                    TaskStep::Details => {
-                                        if let Some(cur_task) = cur_folder.get_selected_task() {
-                                            cur_task.task = input.value().to_string();
+                                    history.record(describe("edit", &names), edits);
+
+                                    input_status = InputStatus::Empty
+                                }
+                                InputRequestType::ConfirmDelete { .. } => {
+                                    if input.value().to_uppercase() == "Y" {
+                                        let targets = cur_folder.marked_or_selected();
+                                        let mut edits = stop_timer_within(cur_folder, &targets)
+                                            .into_iter()
+                                            .collect::<Vec<_>>();
+                                        let mut names = vec![];
+                                        for id in targets {
+                                            let Some(index) = cur_folder.position(id) else {
+                                                continue;
+                                            };
+                                            if let Some(item) = cur_folder.remove_at(index) {
+                                                trash.push(
+                                                    cur_folder.id,
+                                                    path.clone(),
+                                                    item.clone(),
+                                                );
+                                                names.push(item.name().to_string());
+                                                edits.push(Edit::Delete {
+                                                    parent: cur_folder.id,
+                                                    path: path.clone(),
+                                                    index,
+                                                    item,
+                                                    file: TaskFile::Trash,
+                                                });
+                                            }
                                         }
-                                        input_status = InputStatus::Empty
+                                        cur_folder.clear_marks();
+                                        history.record(describe("delete", &names), edits);
                                     }
-                                    TaskStep::Status => {
-                                        if let Some(cur_task) = cur_folder.get_selected_task() {
-                                            cur_task.status.status = input.value().to_string();
+                                    input_status = InputStatus::Empty
+                                }
+                                InputRequestType::ConfirmEmpty => {
+                                    if input.value().to_uppercase() == "Y" {
+                                        match selected_tab {
+                                            5 => trash.clear(),
+                                            _ => complete.clear(),
                                         }
-                                        input_status =
-                                            InputStatus::Request(InputRequestType::EditTask {
-                                                step: TaskStep::StatusColor,
-                                            });
+
+                                        // The history might point into the emptied store
+                                        history.clear();
                                     }
-                                    TaskStep::StatusColor => {
-                                        if let Some(cur_task) = cur_folder.get_selected_task() {
-                                            if let Ok(color) =
-                                                input.value().to_string().parse::<u8>()
-                                            {
-                                                cur_task.status.color = color;
-                                            }
+                                    input_status = InputStatus::Empty
+                                }
+                                InputRequestType::ConfirmQuit => {
+                                    match input.value().to_uppercase().as_str() {
+                                        "S" => match save(
+                                            &workspace, &folder, &trash, &complete, &config,
+                                        ) {
+                                            Ok(_) => break,
+                                            // Stay on the question so nothing gets lost
+                                            Err(e) => message = format!("Save failed: {}", e),
+                                        },
+                                        "D" => break,
+                                        _ => input_status = InputStatus::Empty,
+                                    }
+                                }
+                                InputRequestType::ConfirmReload => {
+                                    let answer = input.value().to_uppercase();
+                                    match answer.as_str() {
+                                        _ if workspace.modified().is_none() => {
+                                            message =
+                                                "The file is gone from disk, kept your changes"
+                                                    .to_string()
                                         }
+                                        "L" | "M" => {
+                                            let Some((disk, disk_trash, disk_complete)) = load(
+                                                terminal,
+                                                &workspace,
+                                                &config,
+                                                &mut message,
+                                                Folder::read,
+                                            )?
+                                            else {
+                                                break;
+                                            };
 
-                                        input_status = InputStatus::Empty
+                                            history.clear();
+                                            if answer == "M" {
+                                                // Ours win, with their edits, moves and deletes,
+                                                // and only what is new on disk comes in unsaved
+                                                let mut removed = trash.ids();
+                                                removed.extend(complete.ids());
+                                                folder.merge_from(&disk, &removed);
+                                                trash.merge_from(&disk_trash, &folder);
+                                                complete.merge_from(&disk_complete, &folder);
+                                                message =
+                                                    "Merged with the changes on disk".to_string();
+                                            } else {
+                                                (folder, trash, complete) =
+                                                    (disk, disk_trash, disk_complete);
+                                                saved_version = history.version();
+                                                message = "Loaded the changes on disk".to_string();
+                                            }
+                                        }
+                                        _ => {
+                                            message =
+                                                "Kept your changes, saving will overwrite the file"
+                                                    .to_string()
+                                        }
                                     }
-                                },
-                                InputRequestType::ConfirmDelete => {
-                                    if input.value().to_uppercase() == "Y" {
-                                        cur_folder.delete_selected();
+                                    modified = workspace.modified();
+                                    input_status = InputStatus::Empty
+                                }
+                                InputRequestType::Filter => {
+                                    filter.query = input.value().to_string();
+                                    input_status = InputStatus::Empty
+                                }
+                                InputRequestType::AddChecklistItem { index } => {
+                                    // New items go below the selected one
+                                    let text = input.value().to_string();
+                                    let mut at = index;
+                                    if !text.is_empty() {
+                                        change_checklist(
+                                            cur_folder,
+                                            &mut history,
+                                            &statuses,
+                                            "add",
+                                            |task| {
+                                                at = match task.checklist.is_empty() {
+                                                    true => 0,
+                                                    false => (index + 1).min(task.checklist.len()),
+                                                };
+                                                task.checklist.insert(
+                                                    at,
+                                                    ChecklistItem { text, done: false },
+                                                );
+                                            },
+                                        );
+                                    }
+                                    input_status = InputStatus::Checklist { selected: at }
+                                }
+                                InputRequestType::EditChecklistItem { index } => {
+                                    let text = input.value().to_string();
+                                    change_checklist(
+                                        cur_folder,
+                                        &mut history,
+                                        &statuses,
+                                        "edit",
+                                        |task| {
+                                            if let Some(item) = task.checklist.get_mut(index) {
+                                                item.text = text;
+                                            }
+                                        },
+                                    );
+                                    input_status = InputStatus::Checklist { selected: index }
+                                }
+                                InputRequestType::NewWorkspace => {
+                                    match Workspace::named(input.value()) {
+                                        Ok(next) => switch_to = Some(next),
+                                        Err(e) => message = e.to_string(),
                                     }
                                     input_status = InputStatus::Empty
                                 }
                             }
                             input = input.with_value("".to_string())
                         }
+                        KeyCode::Tab
+                            if matches!(
+                                request,
+                                InputRequestType::EditTask {
+                                    step: TaskStep::Tags
+                                }
+                            ) =>
+                        {
+                            if let Some(completed) = tags::complete(&folder, input.value()) {
+                                input = input.with_value(completed);
+                            }
+                        }
                         _ => {
                             input.handle_event(&Event::Key(key_event));
                         }
@@ -365,13 +1560,630 @@ fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> anyhow::Result<()>
                 }
             }
         }
+
+        if let Some(next) = switch_to.take() {
+            // Keep what was done in this workspace before leaving it, staying if that fails
+            let saved = match history.version() != saved_version {
+                true => save(&workspace, &folder, &trash, &complete, &config).map(|_| ()),
+                false => Ok(()),
+            };
+            if let Err(e) = saved {
+                message = format!("Save failed, staying in {}: {}", workspace.name, e);
+                continue;
+            }
+
+            match load(
+                terminal,
+                &next,
+                &config,
+                &mut message,
+                Folder::read_or_create,
+            )? {
+                Some(loaded) => {
+                    (folder, trash, complete) = loaded;
+                    statuses = Statuses::read_or_create(&next)?;
+                    workspace = next;
+
+                    selected.clear();
+                    history.clear();
+                    (saved_version, last_save) = (history.version(), Instant::now());
+                    modified = workspace.modified();
+                }
+                None => break,
+            }
+        }
+
+        let autosave = match config.autosave {
+            Autosave::Off => false,
+            Autosave::OnChange => true,
+            Autosave::Interval(seconds) => last_save.elapsed() >= Duration::from_secs(seconds),
+        };
+
+        if autosave && history.version() != saved_version {
+            match save(&workspace, &folder, &trash, &complete, &config) {
+                Ok(warning) => {
+                    (saved_version, last_save) = (history.version(), Instant::now());
+                    modified = workspace.modified();
+                    if let Some(warning) = warning {
+                        message = warning;
+                    }
+                }
+                Err(e) => message = format!("Autosave failed: {}", e),
+            }
+        }
     }
     Ok(())
 }
 
+// Write every file of the workspace, backing up the old main file first. A backup that fails
+// doesn't stop the files from being written, it comes back as a warning to show instead
+fn save(
+    workspace: &Workspace,
+    folder: &Folder,
+    trash: &Store,
+    complete: &Store,
+    config: &Config,
+) -> anyhow::Result<Option<String>> {
+    let backup = backup::create(
+        workspace,
+        config.backup_count,
+        config.backup_interval_minutes,
+    );
+    folder.save(workspace)?;
+    trash.save(workspace)?;
+    complete.save(workspace)?;
+    Ok(backup
+        .err()
+        .map(|e| format!("Saved, but the backup failed: {}", e)))
+}
+
+// Put the next instance in place of every recurring task that is done, sending the finished one
+// to the completed tasks. Returns the edits, to go along with whatever finished the tasks
+fn roll_over(folder: &mut Folder, complete: &mut Store, statuses: &Statuses) -> Vec<Edit> {
+    let mut edits = vec![];
+    for (folders, id) in recur::finished(folder, |status| statuses.is_done(status)) {
+        let path = folder.path_names(&folders);
+        let Ok(parent) = folder.get_folder(folders) else {
+            continue;
+        };
+        let Some(index) = parent.position(id) else {
+            continue;
+        };
+        edits.extend(stop_timer_within(parent, &[id]));
+        let Some(Item::Task(mut done)) = parent.remove(id) else {
+            continue;
+        };
+
+        let next = recur::next_instance(&done, statuses.initial());
+        done.recur = None;
+        complete.push(parent.id, path.clone(), Item::Task(done.clone()));
+        edits.push(Edit::Delete {
+            parent: parent.id,
+            path,
+            index,
+            item: Item::Task(done),
+            file: TaskFile::Complete,
+        });
+
+        if let Some(next) = next {
+            let item = Item::Task(next);
+            parent.insert_at(index, item.clone());
+            edits.push(Edit::Insert {
+                parent: parent.id,
+                index,
+                item,
+            });
+        }
+    }
+    edits
+}
+
+// Move the items into the folder `to` as one action, returning what happened
+fn move_items(
+    folder: &mut Folder,
+    history: &mut History,
+    ids: &[Id],
+    to: Id,
+    index: Option<usize>,
+) -> String {
+    let mut edits = vec![];
+    let mut names = vec![];
+    let mut refused = vec![];
+    for &id in ids {
+        let Some(from) = folder.parent_of(id) else {
+            continue;
+        };
+        let Some((from_index, name)) = folder.find_folder(from).and_then(|source| {
+            let index = source.position(id)?;
+            Some((index, source.item_at(index)?.name().to_string()))
+        }) else {
+            continue;
+        };
+
+        // A folder can't go inside itself, the other items still move
+        let Some(to_index) = folder.move_item(id, from, to, index) else {
+            refused.push(name);
+            continue;
+        };
+
+        // Tasks remember which folders they went through
+        let moved = format!(
+            "moved from {} to {}",
+            folder.path_string(from),
+            folder.path_string(to)
+        );
+        if let Some(target) = folder.find_folder(to) {
+            target.select(id);
+        }
+
+        names.push(name);
+        edits.push(Edit::Move {
+            id,
+            from,
+            from_index,
+            to,
+            to_index,
+        });
+        if let Some(task) = folder.find_task(id).filter(|_| from != to) {
+            let before = task.clone();
+            task.log(moved);
+            edits.push(Edit::task(before, task.clone()));
+        }
+    }
+
+    let mut message = match names.is_empty() {
+        true => "Nothing was moved".to_string(),
+        false => {
+            let description = describe("move", &names);
+            let message = format!("Moved {}", &description["move ".len()..]);
+            history.record(description, edits);
+            message
+        }
+    };
+    if !refused.is_empty() {
+        message = format!("{}, can't move {} into itself", message, refused.join(", "));
+    }
+    message
+}
+
+// Change the checklist of the selected task and record it, if anything changed
+fn change_checklist(
+    cur_folder: &mut Folder,
+    history: &mut History,
+    statuses: &Statuses,
+    action: &str,
+    change: impl FnOnce(&mut Task),
+) {
+    let Some(task) = cur_folder.get_selected_task() else {
+        return;
+    };
+
+    let from = task.clone();
+    change(task);
+    if *task != from {
+        task.record_changes(&from, statuses.is_done(&task.status));
+        history.record(
+            format!("{} checklist item in {}", action, task.title),
+            vec![Edit::task(from, task.clone())],
+        );
+    }
+}
+
+// Start the task's timer, stopping whichever other one is running, or stop it if it is running
+fn toggle_timer(folder: &mut Folder, history: &mut History, id: Id) -> String {
+    let now = Local::now().naive_local();
+    let mut edits = vec![];
+
+    let running = folder.running_task().map(|found| found.task.id);
+    if let Some(task) = running.and_then(|running| folder.find_task(running)) {
+        let from = task.clone();
+        task.stop_timer(now);
+        edits.push(Edit::task(from, task.clone()));
+    }
+
+    let Some(task) = folder.find_task(id) else {
+        return String::new();
+    };
+    let message = match running == Some(id) {
+        true => {
+            history.record(format!("stop timer on {}", task.title), edits);
+            format!("Stopped the timer on {}", task.title)
+        }
+        false => {
+            let from = task.clone();
+            task.time.push(TimeEntry {
+                start: now,
+                end: None,
+            });
+            edits.push(Edit::task(from, task.clone()));
+            history.record(format!("start timer on {}", task.title), edits);
+            format!("Started the timer on {}, <t> stops it", task.title)
+        }
+    };
+    message
+}
+
+// Stop the running timer if its task is one of the items or inside them, before they leave the
+// tree. Otherwise it keeps counting in the trash or the completed tasks
+fn stop_timer_within(folder: &mut Folder, ids: &[Id]) -> Option<Edit> {
+    let running = folder
+        .running_task()
+        .filter(|found| {
+            ids.contains(&found.task.id) || found.folders.iter().any(|id| ids.contains(id))
+        })?
+        .task
+        .id;
+
+    let task = folder.find_task(running)?;
+    let from = task.clone();
+    task.stop_timer(Local::now().naive_local());
+    Some(Edit::task(from, task.clone()))
+}
+
+// Make the task wait on the blocker, or stop waiting if it already does. Blockers that would
+// have tasks waiting on each other are refused
+fn toggle_blocker(
+    folder: &mut Folder,
+    history: &mut History,
+    id: Id,
+    blocker: Id,
+    name: &str,
+) -> String {
+    let adding = !folder
+        .find_task(id)
+        .is_some_and(|task| task.blocked_by.contains(&blocker));
+    if adding && deps::creates_cycle(folder, id, blocker) {
+        return format!("Can't wait on {}, it already waits on this task", name);
+    }
+    let Some(task) = folder.find_task(id) else {
+        return String::new();
+    };
+
+    let from = task.clone();
+    let (action, message) = match adding {
+        true => {
+            task.blocked_by.push(blocker);
+            ("block", format!("blocked by {}", name))
+        }
+        false => {
+            task.blocked_by.retain(|id| *id != blocker);
+            ("unblock", format!("no longer blocked by {}", name))
+        }
+    };
+    task.log(message.clone());
+    history.record(
+        format!("{} {}", action, task.title),
+        vec![Edit::task(from, task.clone())],
+    );
+
+    format!("{} is {}", task.title, message)
+}
+
+// The statuses the picker offers, only the ones a single task can go to
+// The statuses at least one of the tasks the picker is for can go to
+fn status_options<'a>(statuses: &'a Statuses, cur_folder: &mut Folder) -> Vec<&'a StatusDef> {
+    let from = cur_folder
+        .marked_or_selected()
+        .into_iter()
+        .filter_map(|id| cur_folder.find_task(id).map(|task| task.status.clone()))
+        .collect::<Vec<_>>();
+
+    statuses
+        .options(None)
+        .into_iter()
+        .filter(|def| from.iter().any(|status| statuses.can_go(status, def)))
+        .collect()
+}
+
+// Change every marked task, or else the selected one, as one action. Returns the changed tasks
+fn change_tasks(
+    cur_folder: &mut Folder,
+    history: &mut History,
+    statuses: &Statuses,
+    action: &str,
+    change: impl Fn(&mut Task),
+) -> Vec<Task> {
+    let mut edits = vec![];
+    let mut names = vec![];
+    let mut changed = vec![];
+    for id in cur_folder.marked_or_selected() {
+        let Some(task) = cur_folder.find_task(id) else {
+            continue;
+        };
+
+        let from = task.clone();
+        change(task);
+        if *task != from {
+            task.record_changes(&from, statuses.is_done(&task.status));
+            names.push(task.title.clone());
+            changed.push(task.clone());
+            edits.push(Edit::task(from, task.clone()));
+        }
+    }
+
+    history.record(describe(action, &names), edits);
+    changed
+}
+
+fn set_status(
+    cur_folder: &mut Folder,
+    history: &mut History,
+    statuses: &Statuses,
+    new: impl Fn(&Status) -> Option<Status>,
+) -> String {
+    let changed = change_tasks(cur_folder, history, statuses, "set status of", |task| {
+        if let Some(status) = new(&task.status) {
+            task.status = status;
+        }
+    });
+
+    match changed.as_slice() {
+        [] => "No status to change to".to_string(),
+        [task] => format!("{} is now {}", task.title, task.status.status),
+        _ => format!("Changed the status of {} tasks", changed.len()),
+    }
+}
+
+fn set_priority(
+    cur_folder: &mut Folder,
+    history: &mut History,
+    statuses: &Statuses,
+    priority: Priority,
+) -> String {
+    let changed = change_tasks(cur_folder, history, statuses, "set priority of", |task| {
+        task.priority = priority
+    });
+
+    match changed.as_slice() {
+        [] => String::new(),
+        [task] => format!("{} now has {} priority", task.title, priority.name()),
+        _ => format!("Changed the priority of {} tasks", changed.len()),
+    }
+}
+
+// Describe an action on one or more items, like "delete Groceries" or "delete 3 items"
+fn describe(action: &str, names: &[String]) -> String {
+    match names {
+        [name] => format!("{} {}", action, name),
+        _ => format!("{} {} items", action, names.len()),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_list<B: Backend>(
+    frame: &mut Frame<B>,
+    chunks: &Chunks,
+    cur_folder: &mut Folder,
+    blocked: &HashSet<Id>,
+    is_done: &dyn Fn(&Status) -> bool,
+    checklist: Option<usize>,
+    links: (&[deps::Link], Option<usize>),
+    scroll: &mut Scroll,
+) {
+    let mut title = match cur_folder.marked().len() {
+        0 => "Tasks".to_string(),
+        count => format!("Tasks ({} marked)  <v> MARK  <esc> CLEAR", count),
+    };
+    if cur_folder.sort != SortMode::Manual {
+        title.push_str(&format!("  sorted by {}", cur_folder.sort.name()));
+    }
+
+    let list = cur_folder.as_list_widget(blocked, is_done).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain),
+    );
+    frame.render_widget(list, chunks.left_menu());
+
+    if let Some(task) = cur_folder.get_selected_task() {
+        render_task(frame, chunks, task, checklist, links, scroll);
+    } else if let Some(folder) = cur_folder.get_selected_folder() {
+        let split = Layout::new()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(4), Constraint::Min(3)])
+            .split(chunks.right_menu());
+
+        let rollup = folder.rollup(Local::now().naive_local(), is_done);
+        let summary = Paragraph::new(vec![
+            Line::from(format!("Open tasks: {} of {}", rollup.open, rollup.total)),
+            Line::from(format!(
+                "Estimated: {}   Tracked: {}   Remaining: {}",
+                format_duration(rollup.estimated),
+                format_duration(rollup.tracked),
+                format_duration(rollup.remaining)
+            )),
+        ])
+        .block(
+            Block::default()
+                .title("Rollup")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double),
+        );
+        frame.render_widget(summary, split[0]);
+
+        let details = folder.as_list_widget(blocked, is_done).block(
+            Block::default()
+                .title("Inner Tasks")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double),
+        );
+
+        frame.render_widget(details, split[1]);
+    }
+}
+
+// Show a task found outside the list, with nothing in it to select
+fn render_task_details<B: Backend>(
+    frame: &mut Frame<B>,
+    chunks: &Chunks,
+    task: &Task,
+    scroll: &mut Scroll,
+) {
+    render_task(frame, chunks, task, None, (&[], None), scroll);
+}
+
+// Show the task in the right pane, with the checklist item selected if it is being edited and
+// the dependency selected while jumping between them
+fn render_task<B: Backend>(
+    frame: &mut Frame<B>,
+    chunks: &Chunks,
+    task: &Task,
+    checklist: Option<usize>,
+    (links, link): (&[deps::Link], Option<usize>),
+    scroll: &mut Scroll,
+) {
+    let border = Block::default()
+        .title("Task Details")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double);
+
+    frame.render_widget(border, chunks.right_menu());
+
+    let mut status = vec![Span::styled(
+        task.status.status.clone(),
+        Style::default().fg(Color::Indexed(task.status.color)),
+    )];
+    status.extend(tags::chips(&task.tags));
+
+    let status = Paragraph::new(Line::from(status)).block(
+        Block::default()
+            .title("Status")
+            .borders(border!(TOP))
+            .style(Style::new().fg(Color::White)),
+    );
+
+    frame.render_widget(status, chunks.top_detail());
+
+    // The checklist and the dependencies take the bottom of the details area
+    let show_checklist = !task.checklist.is_empty() || checklist.is_some();
+    let split = Layout::new()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(match show_checklist {
+                true => task.checklist.len() as u16 + 2,
+                false => 0,
+            }),
+            Constraint::Length(match links.is_empty() {
+                true => 0,
+                false => links.len() as u16 + 1,
+            }),
+        ])
+        .split(chunks.detail());
+
+    if show_checklist {
+        checklist::render(frame, split[1], task, checklist);
+    }
+    if !links.is_empty() {
+        deps::render(frame, split[2], links, link);
+    }
+    let details_area = split[0];
+
+    // Long details scroll, the top border takes a row
+    let lines = markdown::to_lines(&task.task, details_area.width);
+    let height = details_area.height.saturating_sub(1);
+    let max = (lines.len() as u16).saturating_sub(height);
+    let offset = scroll.offset(task.id, max);
+    let title = match max {
+        0 => "Details".to_string(),
+        _ => format!(
+            "Details {}/{}  <pgup> <pgdn> SCROLL",
+            offset + height,
+            lines.len()
+        ),
+    };
+
+    let details = Paragraph::new(lines).scroll((offset, 0)).block(
+        Block::new()
+            .title(title)
+            .borders(border!(TOP))
+            .border_type(BorderType::Plain),
+    );
+
+    frame.render_widget(details, details_area);
+
+    let mut misc = vec![];
+    if let Some(start) = task.start {
+        misc.push(Line::from(format!(
+            "Start: {}",
+            format_when(start, task.start_time)
+        )));
+    }
+    if let Some((done, total)) = task.progress() {
+        misc.push(Line::from(format!("Checklist: {}/{}", done, total)));
+    }
+    if let Some(rule) = &task.recur {
+        misc.push(Line::from(format!("Repeats: {}", rule)));
+    }
+    if let Some(estimate) = task.estimate() {
+        misc.push(Line::from(format!(
+            "Estimate: {}",
+            format_duration(estimate)
+        )));
+    }
+    if !task.time.is_empty() {
+        let tracked = format_duration(task.tracked(Local::now().naive_local()));
+        misc.push(Line::from(match task.running() {
+            Some(_) => format!("Tracked: {} (running)", tracked),
+            None => format!("Tracked: {}", tracked),
+        }));
+    }
+    if let (Some(due), Some(due_at)) = (task.due, task.due_at()) {
+        misc.push(Line::from(Span::styled(
+            format!(
+                "Due: {} ({})",
+                format_when(due, task.due_time),
+                relative(due_at, Local::now().naive_local())
+            ),
+            Style::default().fg(task.due_color()),
+        )));
+    }
+
+    for (label, when) in [
+        ("Created", task.created),
+        ("Modified", task.modified),
+        ("Completed", task.completed),
+    ] {
+        if let Some(when) = when {
+            misc.push(Line::from(format!(
+                "{}: {}",
+                label,
+                format_when(when.date(), Some(when.time()))
+            )));
+        }
+    }
+    // Newest changes first, as many as fit
+    if !task.log.is_empty() {
+        misc.push(Line::from(Span::styled(
+            "Activity",
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+    }
+    for entry in task.log.iter().rev() {
+        misc.push(Line::from(vec![
+            Span::styled(
+                format_when(entry.at.date(), Some(entry.at.time())),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::raw(format!("  {}", entry.what)),
+        ]));
+    }
+
+    let misc = Paragraph::new(misc).block(
+        Block::new()
+            .title("Misc")
+            .borders(border!(TOP))
+            .border_type(BorderType::Plain),
+    );
+
+    frame.render_widget(misc, chunks.bottom_detail());
+}
+
 fn render_tabs<B: Backend>(
     frame: &mut Frame<B>,
     chunks: &Chunks,
+    title: String,
     selected_tab: usize,
     tabs: Vec<&'static str>,
 ) {
@@ -380,7 +2192,7 @@ fn render_tabs<B: Backend>(
     let tabs = Tabs::new(titles)
         .block(
             Block::default()
-                .title("R-Tasks")
+                .title(title)
                 .borders(Borders::ALL)
                 .border_type(ratatui::widgets::BorderType::Rounded)
                 .style(Style::default().fg(Color::White)),
@@ -392,25 +2204,78 @@ fn render_tabs<B: Backend>(
     frame.render_widget(tabs, chunks.title_bar())
 }
 
-fn render_help<B: Backend>(frame: &mut Frame<B>, chunks: &Chunks) {
-    let help = Paragraph::new(vec![
-        Line::from(" <q> QUIT "),
-        Line::from(" <n> NEW "),
-        Line::from(" <e> EDIT "),
-        Line::from(" <d> DELETE "),
-        Line::from(" <w> SAVE "),
-    ])
-    .style(Style::default().fg(Color::LightCyan))
-    .alignment(Alignment::Left)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .style(Style::default().fg(Color::White))
-            .title("Controls")
-            .border_type(BorderType::Plain),
-    );
+// The keys that change tasks only show on the list, where the task they change is selected
+fn render_help<B: Backend>(frame: &mut Frame<B>, chunks: &Chunks, list: bool) {
+    let lines = [
+        (" <q> QUIT ", false),
+        (" <n> NEW ", true),
+        (" <e> EDIT ", true),
+        (" <d> DELETE ", true),
+        (" <c> COMPLETE ", true),
+        (" <w> SAVE ", false),
+        (" <b> BACKUPS ", false),
+        (" <o> WORKSPACES ", false),
+        (" <m> MOVE TO ", true),
+    ];
+
+    let lines = lines
+        .into_iter()
+        .filter(|(_, changes_tasks)| list || !changes_tasks)
+        .map(|(line, _)| Line::from(line))
+        .collect();
+    render_menu(frame, chunks, "Controls", lines);
+}
 
-    frame.render_widget(help, chunks.message_popup());
+// Where Up and Down move the selection of a list with len rows, staying inside it
+fn move_selection(key: KeyCode, index: usize, len: usize) -> usize {
+    match key {
+        KeyCode::Up => index.saturating_sub(1),
+        KeyCode::Down => (index + 1).min(len.saturating_sub(1)),
+        _ => index,
+    }
+}
+
+// Draw a list to pick from over the right pane, scrolled to keep the selected row in view
+fn render_picker<B: Backend>(
+    frame: &mut Frame<B>,
+    chunks: &Chunks,
+    title: &str,
+    items: Vec<ListItem>,
+    selected: usize,
+) {
+    let list = List::new(items)
+        .style(Style::default().fg(Color::LightCyan))
+        .highlight_style(Style::default().bg(Color::DarkGray))
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .style(Style::default().fg(Color::White)),
+        );
+
+    let mut state = ListState::default();
+    state.select(Some(selected));
+
+    frame.render_widget(Clear, chunks.right_menu());
+    frame.render_stateful_widget(list, chunks.right_menu(), &mut state);
+}
+
+// Draw a menu of keys in the bottom right corner, just big enough for its lines
+fn render_menu<B: Backend>(frame: &mut Frame<B>, chunks: &Chunks, title: &str, lines: Vec<Line>) {
+    let area = chunks.message_popup(title, &lines);
+    let menu = Paragraph::new(lines)
+        .style(Style::default().fg(Color::LightCyan))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(title)
+                .border_type(BorderType::Plain),
+        );
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(menu, area);
 }
 
 struct Chunks {
@@ -418,7 +2283,7 @@ struct Chunks {
     sub: Rc<[Rect]>,
     detail: Rc<[Rect]>,
     input_popup: Rect,
-    message_popup: Rect,
+    screen: Rect,
 }
 
 impl Chunks {
@@ -426,6 +2291,10 @@ impl Chunks {
         self.main[0]
     }
 
+    pub fn status_line(&self) -> Rect {
+        self.main[2]
+    }
+
     pub fn left_menu(&self) -> Rect {
         self.sub[0]
     }
@@ -450,15 +2319,35 @@ impl Chunks {
         self.input_popup
     }
 
-    pub fn message_popup(&self) -> Rect {
-        self.message_popup
+    pub fn message_popup(&self, title: &str, lines: &[Line]) -> Rect {
+        let width = lines
+            .iter()
+            .map(Line::width)
+            .chain([title.chars().count()])
+            .max()
+            .unwrap_or_default() as u16
+            + 2;
+        let (width, height) = (
+            width.min(self.screen.width),
+            (lines.len() as u16 + 2).min(self.screen.height),
+        );
+        Rect::new(
+            self.screen.right() - width,
+            self.screen.bottom() - height,
+            width,
+            height,
+        )
     }
 }
 
 fn make_chunks<T: Backend>(frame: &Frame<T>) -> Chunks {
     let main_chunks = Layout::new()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(10)])
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(10),
+            Constraint::Length(1),
+        ])
         .split(frame.size());
 
     let sub_chunks = Layout::new()
@@ -470,8 +2359,8 @@ fn make_chunks<T: Backend>(frame: &Frame<T>) -> Chunks {
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
-            Constraint::Percentage(100),
-            Constraint::Min(3),
+            Constraint::Percentage(60),
+            Constraint::Percentage(40),
         ])
         .margin(1)
         .split(sub_chunks[1]);
@@ -494,21 +2383,11 @@ fn make_chunks<T: Backend>(frame: &Frame<T>) -> Chunks {
         ])
         .split(temp_popup[1])[1];
 
-    let temp_popup = Layout::new()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(100), Constraint::Min(7)])
-        .split(frame.size());
-
-    let message_popup = Layout::new()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(100), Constraint::Min(15)])
-        .split(temp_popup[1])[1];
-
     Chunks {
         main: main_chunks,
         sub: sub_chunks,
         detail: detail_chunks,
         input_popup,
-        message_popup,
+        screen: frame.size(),
     }
 }
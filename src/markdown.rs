@@ -0,0 +1,403 @@
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::prelude::*;
+
+use crate::task::Id;
+
+// How far the details of the shown task are scrolled, back at the top for another task
+#[derive(Default)]
+pub struct Scroll {
+    task: Option<Id>,
+    offset: u16,
+}
+
+impl Scroll {
+    pub fn by(&mut self, rows: i16) {
+        self.offset = self.offset.saturating_add_signed(rows);
+    }
+
+    // The offset to draw the task at, never past the point where its last line is at the bottom
+    pub fn offset(&mut self, task: Id, max: u16) -> u16 {
+        if self.task != Some(task) {
+            (self.task, self.offset) = (Some(task), 0);
+        }
+        self.offset = self.offset.min(max);
+        self.offset
+    }
+}
+
+// Render the details as Markdown, wrapped to the width
+pub fn to_lines(text: &str, width: u16) -> Vec<Line<'static>> {
+    let mut renderer = Renderer {
+        width: width.max(1) as usize,
+        ..Renderer::default()
+    };
+
+    let options = Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH;
+    for event in Parser::new_ext(text, options) {
+        renderer.event(event);
+    }
+    renderer.flush();
+
+    let mut lines = renderer.lines;
+    while lines.last().is_some_and(|line| line.width() == 0) {
+        lines.pop();
+    }
+    lines
+}
+
+#[derive(Default)]
+struct Renderer {
+    width: usize,
+    lines: Vec<Line<'static>>,
+    // The text of the block being read, written out wrapped once it ends
+    spans: Vec<Span<'static>>,
+    styles: Vec<Style>,
+    // The next number of each open list, None for bullets
+    lists: Vec<Option<u64>>,
+    // How far the content of each open list item is indented
+    indents: Vec<usize>,
+    // The bullet or number of an item that has no lines yet
+    marker: Option<Span<'static>>,
+    quotes: usize,
+    code: Option<String>,
+    link: Option<String>,
+}
+
+impl Renderer {
+    fn style(&self) -> Style {
+        self.styles
+            .iter()
+            .fold(Style::default(), |style, next| style.patch(*next))
+    }
+
+    fn push(&mut self, text: String) {
+        let style = self.style();
+        self.spans.push(Span::styled(text, style));
+    }
+
+    fn blank(&mut self) {
+        if self.lines.last().is_some_and(|line| line.width() > 0) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    // What goes before the first line of a block and the lines it wraps onto
+    fn prefixes(&mut self) -> (Vec<Span<'static>>, Vec<Span<'static>>) {
+        let quote = Span::styled(
+            "│ ".repeat(self.quotes),
+            Style::default().fg(Color::DarkGray),
+        );
+        let indent = self.indents.iter().sum::<usize>();
+
+        let rest = vec![quote.clone(), Span::raw(" ".repeat(indent))];
+        let first = match self.marker.take() {
+            Some(marker) => vec![
+                quote,
+                Span::raw(" ".repeat(indent.saturating_sub(marker.width()))),
+                marker,
+            ],
+            None => rest.clone(),
+        };
+        (first, rest)
+    }
+
+    fn flush(&mut self) {
+        if self.spans.is_empty() {
+            return;
+        }
+        let spans = std::mem::take(&mut self.spans);
+        let (first, rest) = self.prefixes();
+        let lines = wrap(spans, first, rest, self.width);
+        self.lines.extend(lines);
+    }
+
+    // An item with nothing in it before its end or a list inside it still shows its bullet
+    fn marker_line(&mut self) {
+        if self.marker.is_some() {
+            let (first, _) = self.prefixes();
+            self.lines.push(Line::from(first));
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => match &mut self.code {
+                Some(code) => code.push_str(&text),
+                None => self.push(text.to_string()),
+            },
+            Event::Code(code) => self.spans.push(Span::styled(
+                code.to_string(),
+                self.style().fg(Color::LightYellow),
+            )),
+            Event::SoftBreak => self.push(" ".to_string()),
+            Event::HardBreak => self.push("\n".to_string()),
+            Event::Rule => {
+                self.flush();
+                self.blank();
+                self.lines.push(Line::styled(
+                    "─".repeat(self.width),
+                    Style::default().fg(Color::DarkGray),
+                ));
+                self.blank();
+            }
+            Event::TaskListMarker(done) => {
+                let marker = match done {
+                    true => Span::styled("[x] ", Style::default().fg(Color::LightGreen)),
+                    false => Span::raw("[ ] "),
+                };
+                // The box stands in for the bullet
+                match (&self.marker, self.indents.last_mut()) {
+                    (Some(_), Some(indent)) => {
+                        *indent = marker.width();
+                        self.marker = Some(marker);
+                    }
+                    _ => self.spans.push(marker),
+                }
+            }
+            Event::InlineHtml(html) | Event::Html(html) => self.push(html.to_string()),
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => self.flush(),
+            Tag::Heading { level, .. } => {
+                self.flush();
+                self.blank();
+                self.styles.push(match level {
+                    HeadingLevel::H1 => Style::default()
+                        .fg(Color::LightCyan)
+                        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    HeadingLevel::H2 => Style::default()
+                        .fg(Color::LightCyan)
+                        .add_modifier(Modifier::BOLD),
+                    _ => Style::default().add_modifier(Modifier::BOLD),
+                });
+            }
+            Tag::BlockQuote(_) => {
+                self.flush();
+                self.quotes += 1;
+                self.styles
+                    .push(Style::default().add_modifier(Modifier::ITALIC));
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush();
+                self.blank();
+                if let CodeBlockKind::Fenced(language) = kind {
+                    if !language.is_empty() {
+                        let (first, _) = self.prefixes();
+                        let mut line = first;
+                        line.push(Span::styled(
+                            language.to_string(),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                        self.lines.push(Line::from(line));
+                    }
+                }
+                self.code = Some(String::new());
+            }
+            Tag::List(start) => {
+                self.flush();
+                self.marker_line();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "• ".to_string(),
+                };
+                self.indents.push(marker.chars().count());
+                self.marker = Some(Span::styled(marker, Style::default().fg(Color::LightBlue)));
+            }
+            Tag::Emphasis => self
+                .styles
+                .push(Style::default().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self
+                .styles
+                .push(Style::default().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => self
+                .styles
+                .push(Style::default().add_modifier(Modifier::CROSSED_OUT)),
+            Tag::Link { dest_url, .. } => {
+                self.link = Some(dest_url.to_string());
+                self.styles.push(
+                    Style::default()
+                        .fg(Color::LightBlue)
+                        .add_modifier(Modifier::UNDERLINED),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => {
+                self.flush();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            TagEnd::Heading(_) => {
+                self.flush();
+                self.styles.pop();
+                self.blank();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush();
+                self.quotes -= 1;
+                self.styles.pop();
+                self.blank();
+            }
+            TagEnd::CodeBlock => {
+                let code = self.code.take().unwrap_or_default();
+                let (_, prefix) = self.prefixes();
+                let room = self
+                    .width
+                    .saturating_sub(Line::from(prefix.clone()).width());
+                for line in code.trim_end_matches('\n').split('\n') {
+                    // Code keeps its spacing, lines too long for the pane are cut into pieces
+                    let chars = line.chars().collect::<Vec<_>>();
+                    for piece in chars.chunks(room.max(1)).map(String::from_iter) {
+                        let mut spans = prefix.clone();
+                        spans.push(Span::styled(piece, Style::default().fg(Color::LightYellow)));
+                        self.lines.push(Line::from(spans));
+                    }
+                    if chars.is_empty() {
+                        self.lines.push(Line::default());
+                    }
+                }
+                self.blank();
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            TagEnd::Item => {
+                self.flush();
+                self.marker_line();
+                self.indents.pop();
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.styles.pop();
+            }
+            TagEnd::Link => {
+                self.styles.pop();
+                // Show where the link goes unless its text already does
+                if let Some(url) = self.link.take() {
+                    let text = self
+                        .spans
+                        .last()
+                        .map(|span| span.content.to_string())
+                        .unwrap_or_default();
+                    if text != url {
+                        self.spans.push(Span::styled(
+                            format!(" ({})", url),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Fill lines with the words of the spans, keeping their styles. Words too long for a line
+// are broken up and `\n` always starts a new line
+fn wrap(
+    spans: Vec<Span<'static>>,
+    first: Vec<Span<'static>>,
+    rest: Vec<Span<'static>>,
+    width: usize,
+) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    let mut line = first;
+    let mut used = Line::from(line.clone()).width();
+    let start = Line::from(rest.clone()).width();
+    // Whether the line has any words yet, spaces don't carry over the start of a line
+    let mut empty = true;
+
+    for span in spans {
+        for word in split_words(&span.content) {
+            if word == "\n" {
+                lines.push(Line::from(std::mem::replace(&mut line, rest.clone())));
+                (used, empty) = (start, true);
+                continue;
+            }
+
+            let blank = word.trim().is_empty();
+            if blank && empty {
+                continue;
+            }
+
+            let mut word = word.to_string();
+            let mut size = Span::raw(word.as_str()).width();
+            if used + size > width && !empty {
+                lines.push(Line::from(std::mem::replace(&mut line, rest.clone())));
+                (used, empty) = (start, true);
+                if blank {
+                    continue;
+                }
+            }
+
+            // A word longer than a whole line
+            while used + size > width && word.chars().count() > 1 {
+                let room = width.saturating_sub(used).max(1);
+                let head = word.chars().take(room).collect::<String>();
+                word = word.chars().skip(room).collect();
+                line.push(Span::styled(head, span.style));
+                lines.push(Line::from(std::mem::replace(&mut line, rest.clone())));
+                used = start;
+                size = Span::raw(word.as_str()).width();
+            }
+
+            line.push(Span::styled(word, span.style));
+            used += size;
+            empty = false;
+        }
+    }
+
+    lines.push(Line::from(line));
+    lines
+}
+
+// Break text into words, the runs of spaces between them and line breaks
+fn split_words(text: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut start = 0;
+    let mut space = None;
+    for (i, c) in text.char_indices() {
+        let is_space = c == ' ' || c == '\t';
+        if c == '\n' {
+            if start < i {
+                words.push(&text[start..i]);
+            }
+            words.push("\n");
+            (start, space) = (i + 1, None);
+            continue;
+        }
+        if space.is_some_and(|was| was != is_space) && start < i {
+            words.push(&text[start..i]);
+            start = i;
+        }
+        space = Some(is_space);
+    }
+    if start < text.len() {
+        words.push(&text[start..]);
+    }
+    words
+}
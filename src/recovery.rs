@@ -0,0 +1,160 @@
+use std::{
+    fs,
+    io::Stdout,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use crossterm::event::{self, Event, KeyCode};
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{backup, format, task::Folder, workspace::Workspace};
+
+// Shown when tasks.json can't be read, lets the user pick how to get back to a working file.
+// Returns the folder to continue with and a message describing what happened, or None to quit
+pub fn recover(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    workspace: &Workspace,
+    error: anyhow::Error,
+) -> anyhow::Result<Option<(Folder, String)>> {
+    let path = workspace.file.clone();
+    let data = fs::read_to_string(&path).unwrap_or_default();
+
+    let mut show_error = false;
+    let mut message = String::new();
+
+    loop {
+        terminal.draw(|frame| {
+            let mut lines = vec![
+                Line::from(Span::styled(
+                    format!("{} could not be read", path.display()),
+                    Style::default().fg(Color::LightRed),
+                )),
+                Line::from(""),
+                Line::from(" <b> OPEN THE LATEST BACKUP "),
+                Line::from(" <f> START FRESH, MOVING THE BROKEN FILE ASIDE "),
+                Line::from(" <e> SHOW THE ERROR "),
+                Line::from(" <q> QUIT "),
+                Line::from(""),
+                Line::from(Span::styled(
+                    message.clone(),
+                    Style::default().fg(Color::LightYellow),
+                )),
+            ];
+
+            if show_error {
+                lines.extend(error_lines(&error, &data));
+            }
+
+            let screen = Paragraph::new(lines)
+                .style(Style::default().fg(Color::LightCyan))
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .title("R-Tasks Recovery")
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .style(Style::default().fg(Color::White)),
+                );
+
+            frame.render_widget(screen, frame.size());
+        })?;
+
+        if let Event::Key(key) = event::read()? {
+            match key.code {
+                KeyCode::Char('b') => match latest_backup(workspace) {
+                    Ok((folder, backup)) => {
+                        let broken = move_aside(&path)?;
+                        folder.save(workspace)?;
+
+                        return Ok(Some((
+                            folder,
+                            format!(
+                                "Opened backup {}, the broken file was moved to {}",
+                                backup.display(),
+                                broken.display()
+                            ),
+                        )));
+                    }
+                    Err(e) => message = format!("Couldn't open a backup: {}", e),
+                },
+                KeyCode::Char('f') => {
+                    let broken = move_aside(&path)?;
+                    let folder = Folder::default();
+                    folder.save(workspace)?;
+
+                    return Ok(Some((
+                        folder,
+                        format!(
+                            "Started fresh, the broken file was moved to {}",
+                            broken.display()
+                        ),
+                    )));
+                }
+                KeyCode::Char('e') => show_error = !show_error,
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+// The newest backup that can actually be read
+fn latest_backup(workspace: &Workspace) -> anyhow::Result<(Folder, PathBuf)> {
+    for path in backup::list(workspace)? {
+        if let Ok(folder) = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| format::parse(&data))
+        {
+            return Ok((folder, path));
+        }
+    }
+
+    Err(anyhow!("No readable backups were found"))
+}
+
+// Rename the broken file so it isn't lost, but is out of the way
+fn move_aside(path: &Path) -> anyhow::Result<PathBuf> {
+    let mut broken = path.as_os_str().to_owned();
+    broken.push(format!(
+        ".broken-{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    ));
+
+    let broken = PathBuf::from(broken);
+    fs::rename(path, &broken)?;
+    Ok(broken)
+}
+
+// Describe the error, pointing at where in the file it happened if it was a parse error
+fn error_lines<'a>(error: &anyhow::Error, data: &'a str) -> Vec<Line<'a>> {
+    let mut lines = vec![Line::from(Span::styled(
+        error.to_string(),
+        Style::default().fg(Color::LightRed),
+    ))];
+
+    // Errors found after migrating an old file don't have a position in it
+    if let Some(error) = error
+        .downcast_ref::<serde_json::Error>()
+        .filter(|error| error.line() > 0)
+    {
+        lines.push(Line::from(format!(
+            "At line {}, column {}",
+            error.line(),
+            error.column()
+        )));
+
+        if let Some(line) = data.lines().nth(error.line().saturating_sub(1)) {
+            lines.push(Line::from(Span::styled(
+                line,
+                Style::default().fg(Color::White),
+            )));
+            lines.push(Line::from(format!(
+                "{}^",
+                " ".repeat(error.column().saturating_sub(1))
+            )));
+        }
+    }
+
+    lines
+}
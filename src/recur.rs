@@ -0,0 +1,244 @@
+use std::fmt;
+
+use chrono::{Datelike, Days, Local, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::task::{Folder, Id, Status, Task};
+
+// When a recurring task comes back after it is done
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "repeat", rename_all = "snake_case")]
+pub enum Recurrence {
+    Daily,
+    Weekly { days: Vec<Weekday> },
+    Monthly { day: u32 },
+    // Counted from the day it was done instead of from the due date
+    AfterCompletion { days: u32 },
+}
+
+impl Recurrence {
+    // Read rules like `daily`, `weekly mon thu`, `monthly 15` or `every 3 days`. Weekly and
+    // monthly rules without days fall on the same day as `from`
+    pub fn parse(input: &str, from: NaiveDate) -> Option<Self> {
+        let input = input.trim().to_lowercase();
+        let words = input
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>();
+
+        match words.as_slice() {
+            ["daily"] | ["every", "day"] => Some(Recurrence::Daily),
+            ["weekly"] | ["every", "week"] => Some(Recurrence::Weekly {
+                days: vec![from.weekday()],
+            }),
+            ["weekly", days @ ..] => {
+                let mut days = days
+                    .iter()
+                    .map(|day| day.parse::<Weekday>().ok())
+                    .collect::<Option<Vec<_>>>()?;
+                days.sort_by_key(|day| day.num_days_from_monday());
+                days.dedup();
+                Some(Recurrence::Weekly { days })
+            }
+            ["monthly"] | ["every", "month"] => Some(Recurrence::Monthly { day: from.day() }),
+            ["monthly", day] => match day.parse() {
+                Ok(day @ 1..=31) => Some(Recurrence::Monthly { day }),
+                _ => None,
+            },
+            ["every", days] | ["every", days, "days" | "day"] | ["after", days, "days" | "day"] => {
+                let days = days.strip_suffix('d').unwrap_or(days).parse().ok()?;
+                match days {
+                    0 => None,
+                    days => Some(Recurrence::AfterCompletion { days }),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    // The first date the rule falls on after `after`
+    fn following(&self, after: NaiveDate, done: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Recurrence::Daily => after.checked_add_days(Days::new(1)),
+            Recurrence::Weekly { days } => (1..=7)
+                .filter_map(|ahead| after.checked_add_days(Days::new(ahead)))
+                .find(|date| days.is_empty() || days.contains(&date.weekday())),
+            Recurrence::Monthly { day } => {
+                // Short months use their last day
+                let month = after.with_day(1)?;
+                [month, month.checked_add_months(Months::new(1))?]
+                    .into_iter()
+                    .filter_map(|month| {
+                        let last = month.checked_add_months(Months::new(1))?.pred_opt()?.day();
+                        month.with_day((*day).min(last))
+                    })
+                    .find(|date| *date > after)
+            }
+            Recurrence::AfterCompletion { days } => done.checked_add_days(Days::new(*days as u64)),
+        }
+    }
+
+    // When the next instance is due. Fixed schedules go on from the last due date, skipping
+    // the dates that already went by
+    pub fn next_due(&self, due: Option<NaiveDate>, done: NaiveDate) -> Option<NaiveDate> {
+        let mut next = self.following(due.unwrap_or(done), done)?;
+        while next <= done {
+            next = self.following(next, done)?;
+        }
+        Some(next)
+    }
+}
+
+// Written back the way it is typed in
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekly { days } => {
+                let days = days
+                    .iter()
+                    .map(|day| day.to_string().to_lowercase())
+                    .collect::<Vec<_>>();
+                write!(f, "weekly {}", days.join(" "))
+            }
+            Recurrence::Monthly { day } => write!(f, "monthly {}", day),
+            Recurrence::AfterCompletion { days: 1 } => write!(f, "every 1 day"),
+            Recurrence::AfterCompletion { days } => write!(f, "every {} days", days),
+        }
+    }
+}
+
+// The task to put in place of a finished recurring one, due on the next date of its rule
+pub fn next_instance(task: &Task, initial: Status) -> Option<Task> {
+    let rule = task.recur.as_ref()?;
+    let today = Local::now().date_naive();
+    let due = rule.next_due(task.due, today)?;
+
+    let mut next = task.clone();
+    next.id = Id::default();
+
+    // A start date keeps its distance to the due date
+    if let (Some(start), Some(old_due)) = (task.start, task.due) {
+        next.start = Some(start + (due - old_due));
+    }
+    next.due = Some(due);
+    next.status = initial;
+    next.created = Some(Local::now().naive_local());
+    (next.modified, next.completed) = (None, None);
+    next.log.clear();
+    next.time.clear();
+    for item in &mut next.checklist {
+        item.done = false;
+    }
+
+    Some(next)
+}
+
+// The recurring tasks in the tree that are done, with the folders holding them
+pub fn finished(folder: &Folder, is_done: impl Fn(&Status) -> bool) -> Vec<(Vec<Id>, Id)> {
+    folder
+        .all_tasks()
+        .into_iter()
+        .filter(|found| found.task.recur.is_some() && is_done(&found.task.status))
+        .map(|found| (found.folders, found.task.id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn rules() {
+        // A thursday
+        let from = date(2026, 10, 15);
+
+        assert!(matches!(
+            Recurrence::parse("daily", from),
+            Some(Recurrence::Daily)
+        ));
+        assert!(matches!(
+            Recurrence::parse("weekly", from),
+            Some(Recurrence::Weekly { days }) if days == vec![Weekday::Thu]
+        ));
+        assert!(matches!(
+            Recurrence::parse("weekly thu, mon mon", from),
+            Some(Recurrence::Weekly { days }) if days == vec![Weekday::Mon, Weekday::Thu]
+        ));
+        assert!(matches!(
+            Recurrence::parse("monthly", from),
+            Some(Recurrence::Monthly { day: 15 })
+        ));
+        assert!(matches!(
+            Recurrence::parse("every 3 days", from),
+            Some(Recurrence::AfterCompletion { days: 3 })
+        ));
+        assert!(matches!(
+            Recurrence::parse("every 2d", from),
+            Some(Recurrence::AfterCompletion { days: 2 })
+        ));
+
+        for input in [
+            "",
+            "hourly",
+            "monthly 32",
+            "monthly 0",
+            "every 0 days",
+            "weekly funday",
+        ] {
+            assert!(Recurrence::parse(input, from).is_none(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn written_back() {
+        let from = date(2026, 10, 15);
+        for input in ["daily", "weekly mon thu", "monthly 15", "every 3 days"] {
+            let rule = Recurrence::parse(input, from).unwrap();
+            assert_eq!(rule.to_string(), input);
+        }
+    }
+
+    #[test]
+    fn next_dates() {
+        let done = date(2026, 10, 15);
+
+        assert_eq!(
+            Recurrence::Daily.next_due(Some(done), done),
+            Some(date(2026, 10, 16))
+        );
+        // Missed dates are skipped
+        assert_eq!(
+            Recurrence::Daily.next_due(Some(date(2026, 10, 1)), done),
+            Some(date(2026, 10, 16))
+        );
+
+        let weekly = Recurrence::Weekly {
+            days: vec![Weekday::Mon, Weekday::Thu],
+        };
+        assert_eq!(weekly.next_due(Some(done), done), Some(date(2026, 10, 19)));
+
+        // Short months fall on their last day
+        let monthly = Recurrence::Monthly { day: 31 };
+        assert_eq!(
+            monthly.next_due(Some(date(2026, 1, 31)), date(2026, 1, 31)),
+            Some(date(2026, 2, 28))
+        );
+        assert_eq!(
+            monthly.next_due(Some(date(2026, 2, 28)), date(2026, 2, 28)),
+            Some(date(2026, 3, 31))
+        );
+
+        // Counted from when it was done, not when it was due
+        let after = Recurrence::AfterCompletion { days: 3 };
+        assert_eq!(
+            after.next_due(Some(date(2026, 10, 1)), done),
+            Some(date(2026, 10, 18))
+        );
+        assert_eq!(after.next_due(None, done), Some(date(2026, 10, 18)));
+    }
+}
@@ -0,0 +1,157 @@
+use ratatui::{prelude::*, widgets::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    task::{Status, TaskFile},
+    workspace::Workspace,
+};
+
+// One of the statuses a task in the workspace can have
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StatusDef {
+    pub name: String,
+    pub color: u8,
+    // Whether a task with this status counts as finished
+    #[serde(default)]
+    pub done: bool,
+    // The statuses a task can go to from this one, any of them if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub next: Vec<String>,
+}
+
+impl StatusDef {
+    pub fn new(name: &str, color: u8, done: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            color,
+            done,
+            next: vec![],
+        }
+    }
+
+    pub fn to_status(&self) -> Status {
+        Status {
+            status: self.name.clone(),
+            color: self.color,
+        }
+    }
+}
+
+// The statuses defined for a workspace, read from its statuses.json
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Statuses(Vec<StatusDef>);
+
+impl Default for Statuses {
+    fn default() -> Self {
+        Self(vec![
+            StatusDef::new("Incomplete", 5, false),
+            StatusDef::new("In Progress", 3, false),
+            StatusDef::new("Complete", 2, true),
+        ])
+    }
+}
+
+impl Statuses {
+    pub fn read_or_create(workspace: &Workspace) -> anyhow::Result<Self> {
+        let statuses: Self = TaskFile::Statuses.read_or_create(workspace)?;
+        if statuses.0.is_empty() {
+            return Err(anyhow!("statuses.json should define at least one status"));
+        }
+        Ok(statuses)
+    }
+
+    fn get(&self, status: &Status) -> Option<&StatusDef> {
+        self.0.iter().find(|def| def.name == status.status)
+    }
+
+    // Where the status is in the list, for sorting by it. Undefined statuses go last
+    pub fn rank(&self, status: &Status) -> usize {
+        self.0
+            .iter()
+            .position(|def| def.name == status.status)
+            .unwrap_or(self.0.len())
+    }
+
+    // The defined status with the name, ignoring case
+    pub fn named(&self, name: &str) -> Option<Status> {
+        self.0
+            .iter()
+            .find(|def| def.name.eq_ignore_ascii_case(name))
+            .map(StatusDef::to_status)
+    }
+
+    // Whether a task with the status counts as finished
+    pub fn is_done(&self, status: &Status) -> bool {
+        self.get(status).is_some_and(|def| def.done)
+    }
+
+    // The status new tasks start with
+    pub fn initial(&self) -> Status {
+        self.0[0].to_status()
+    }
+
+    // The status completing a task gives it
+    pub fn done(&self) -> Status {
+        self.0
+            .iter()
+            .find(|def| def.done)
+            .map(StatusDef::to_status)
+            .unwrap_or(Status {
+                status: String::from("Complete"),
+                color: 2,
+            })
+    }
+
+    // Whether a task can go from one status to the other.
+    // Statuses that aren't defined, like ones typed in before, can go anywhere
+    fn allowed(&self, from: &Status, to: &StatusDef) -> bool {
+        match self.get(from) {
+            Some(def) => def.next.is_empty() || def.next.contains(&to.name),
+            None => true,
+        }
+    }
+
+    // Whether a task can be set to the status, staying at its own counts
+    pub fn can_go(&self, from: &Status, to: &StatusDef) -> bool {
+        to.name == from.status || self.allowed(from, to)
+    }
+
+    // What the picker offers, every status when there is no task to go from
+    pub fn options(&self, from: Option<&Status>) -> Vec<&StatusDef> {
+        self.0
+            .iter()
+            .filter(|def| from.is_none_or(|from| self.can_go(from, def)))
+            .collect()
+    }
+
+    // The next allowed status after the current one, wrapping around the list
+    pub fn cycle(&self, from: &Status) -> Option<Status> {
+        let start = self
+            .0
+            .iter()
+            .position(|def| def.name == from.status)
+            .map_or(0, |index| index + 1);
+
+        self.0
+            .iter()
+            .cycle()
+            .skip(start)
+            .take(self.0.len())
+            .find(|def| def.name != from.status && self.allowed(from, def))
+            .map(StatusDef::to_status)
+    }
+}
+
+pub fn picker_items(options: &[&StatusDef]) -> Vec<ListItem<'static>> {
+    options
+        .iter()
+        .map(|def| {
+            let name = match def.done {
+                true => format!("{} (done)", def.name),
+                false => def.name.clone(),
+            };
+            ListItem::new(name).style(Style::default().fg(Color::Indexed(def.color)))
+        })
+        .collect()
+}
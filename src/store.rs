@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+
+use chrono::{Duration, Local, NaiveDateTime};
+use ratatui::{prelude::*, widgets::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    history::{Edit, History},
+    markdown::Scroll,
+    render_task_details,
+    task::{Folder, Id, Item, TaskFile, TaskRef},
+    workspace::Workspace,
+    Chunks,
+};
+
+// An item that was taken out of the main tree, along with where it came from
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StoredItem {
+    // The names of the folders it was in, shown in the list and used to recreate them if needed
+    pub path: Vec<String>,
+    // The folder it was taken out of, which it goes back into if that still exists
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Id>,
+    pub item: Item,
+    pub stored: NaiveDateTime,
+}
+
+// Holds the items of the trash or complete files
+pub struct Store {
+    file: TaskFile,
+    items: Vec<StoredItem>,
+    selected: usize,
+}
+
+impl Store {
+    pub fn new(file: TaskFile) -> Self {
+        Self {
+            file,
+            items: vec![],
+            selected: 0,
+        }
+    }
+
+    pub fn read_or_create(workspace: &Workspace, file: TaskFile) -> anyhow::Result<Self> {
+        Ok(Self {
+            items: file.read_or_create(workspace)?,
+            ..Self::new(file)
+        })
+    }
+
+    pub fn save(&self, workspace: &Workspace) -> anyhow::Result<()> {
+        self.file.write(workspace, &self.items)
+    }
+
+    pub fn push(&mut self, parent: Id, path: Vec<String>, item: Item) {
+        self.items.push(StoredItem {
+            path,
+            parent: Some(parent),
+            item,
+            stored: Local::now().naive_local(),
+        });
+    }
+
+    // Take back the item with the id that was stored last, normally the last one pushed
+    pub fn take(&mut self, id: Id) -> Option<StoredItem> {
+        let index = self
+            .items
+            .iter()
+            .rposition(|stored| stored.item.id() == id)?;
+        self.remove(index)
+    }
+
+    pub fn insert(&mut self, index: usize, stored: StoredItem) {
+        self.items.insert(index.min(self.items.len()), stored);
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<StoredItem> {
+        if index >= self.items.len() {
+            return None;
+        }
+
+        let stored = self.items.remove(index);
+        self.adjust_selected(0);
+        Some(stored)
+    }
+
+    // Put the selected item back where it was taken from
+    pub fn restore_selected(&mut self, folder: &mut Folder, history: &mut History) {
+        let store_index = self.selected;
+        let Some(stored) = self.items.get(store_index).cloned() else {
+            return;
+        };
+
+        // Fall back to the folder names if the original folder is gone
+        let parent = match stored.parent {
+            Some(id) if folder.find_folder(id).is_some() => id,
+            _ => folder.get_or_create_folder(stored.path.clone()).id,
+        };
+        let Some(index) = folder
+            .find_folder(parent)
+            .map(|target| target.insert(stored.item.clone()))
+        else {
+            return;
+        };
+        self.remove(store_index);
+
+        history.record(
+            format!("restore {}", stored.item.name()),
+            vec![Edit::Restore {
+                file: self.file,
+                store_index,
+                stored,
+                parent,
+                index,
+            }],
+        );
+    }
+
+    // Every id in the store, including the ones inside stored folders
+    pub fn ids(&self) -> HashSet<Id> {
+        self.items
+            .iter()
+            .flat_map(|stored| stored.item.ids())
+            .collect()
+    }
+
+    // Add the items of the other store that aren't in this one yet or back in the tree
+    pub fn merge_from(&mut self, other: &Store, tree: &Folder) {
+        let mut known = self.ids();
+        known.extend(tree.ids());
+
+        for stored in &other.items {
+            if !known.contains(&stored.item.id()) {
+                self.items.push(stored.clone());
+            }
+        }
+    }
+
+    // Every task in the store, including the ones inside stored folders
+    pub fn all_tasks(&self) -> Vec<TaskRef<'_>> {
+        let mut tasks = vec![];
+        for stored in &self.items {
+            match &stored.item {
+                Item::Task(task) => tasks.push(TaskRef {
+                    path: stored.path.clone(),
+                    folders: vec![],
+                    task,
+                }),
+                Item::Folder(folder) => {
+                    for mut found in folder.all_tasks() {
+                        let mut path = stored.path.clone();
+                        path.push(folder.name.clone());
+                        path.append(&mut found.path);
+                        found.path = path;
+                        found.folders.clear();
+                        tasks.push(found);
+                    }
+                }
+            }
+        }
+        tasks
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.selected = 0;
+    }
+
+    // Drop everything that has been stored for longer than the given amount of days
+    pub fn purge_older_than(&mut self, days: i64) {
+        let cutoff = Local::now().naive_local() - Duration::days(days);
+        self.items.retain(|stored| stored.stored >= cutoff);
+        self.adjust_selected(0);
+    }
+
+    pub fn adjust_selected(&mut self, dist: i32) {
+        let max = (self.items.len() as i32 - 1).max(0);
+
+        self.selected = (self.selected as i32 + dist).clamp(0, max) as usize;
+    }
+
+    pub fn render<B: Backend>(
+        &mut self,
+        frame: &mut Frame<B>,
+        chunks: &Chunks,
+        title: &str,
+        scroll: &mut Scroll,
+    ) {
+        let list = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, stored)| {
+                let mut path = stored.path.clone();
+                path.push(stored.item.name().to_string());
+
+                let color = match stored.item {
+                    Item::Task(_) => Color::LightGreen,
+                    Item::Folder(_) => Color::LightCyan,
+                };
+                let style = match i == self.selected {
+                    true => Style::default().fg(color).bg(Color::DarkGray),
+                    false => Style::default().fg(color),
+                };
+
+                ListItem::new(Line::from(vec![
+                    Span::styled(path.join("/"), style),
+                    Span::styled(
+                        stored.stored.format("  %Y-%m-%d %H:%M").to_string(),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]))
+            })
+            .collect::<Vec<_>>();
+
+        let list = List::new(list).block(
+            Block::default()
+                .title(format!("{}  <r> RESTORE  <E> EMPTY", title))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain),
+        );
+
+        frame.render_widget(list, chunks.left_menu());
+
+        match self
+            .items
+            .get_mut(self.selected)
+            .map(|stored| &mut stored.item)
+        {
+            Some(Item::Task(task)) => render_task_details(frame, chunks, task, scroll),
+            Some(Item::Folder(folder)) => {
+                let details = folder.as_list_widget(&HashSet::new(), &|_| false).block(
+                    Block::default()
+                        .title("Inner Tasks")
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Double),
+                );
+
+                frame.render_widget(details, chunks.right_menu());
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeSet;
+
+use crossterm::event::KeyCode;
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{
+    found::Highlight,
+    markdown::Scroll,
+    render_task_details,
+    task::{Folder, Id, TaskRef},
+    Chunks,
+};
+
+const COLORS: [Color; 6] = [
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::Yellow,
+    Color::LightGreen,
+    Color::LightRed,
+];
+
+// Tags are typed separated by spaces or commas
+pub fn parse(input: &str) -> BTreeSet<String> {
+    input
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| tag.to_lowercase())
+        .collect()
+}
+
+// The same tag always gets the same color
+pub fn color(tag: &str) -> Color {
+    let hash = tag.bytes().fold(0usize, |hash, b| {
+        hash.wrapping_mul(31).wrapping_add(b as usize)
+    });
+    COLORS[hash % COLORS.len()]
+}
+
+pub fn chips(tags: &BTreeSet<String>) -> Vec<Span<'static>> {
+    let mut spans = vec![];
+    for tag in tags {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format!(" {} ", tag),
+            Style::default().fg(Color::Black).bg(color(tag)),
+        ));
+    }
+    spans
+}
+
+// Every tag used anywhere in the tree
+pub fn all(folder: &Folder) -> BTreeSet<String> {
+    folder
+        .all_tasks()
+        .into_iter()
+        .flat_map(|found| found.task.tags.iter().cloned())
+        .collect()
+}
+
+// The known tags the word being typed could be, leaving out the ones already typed
+pub fn suggestions(folder: &Folder, input: &str) -> Vec<String> {
+    let typed = parse(input);
+    let word = match input.ends_with(|c: char| c.is_whitespace() || c == ',') {
+        true => String::new(),
+        false => input
+            .rsplit(|c: char| c.is_whitespace() || c == ',')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase(),
+    };
+
+    all(folder)
+        .into_iter()
+        .filter(|tag| tag.starts_with(&word) && !typed.contains(tag))
+        .collect()
+}
+
+// Finish the word being typed with the first suggestion
+pub fn complete(folder: &Folder, input: &str) -> Option<String> {
+    let tag = suggestions(folder, input).into_iter().next()?;
+    let start = input
+        .rmatch_indices(|c: char| c.is_whitespace() || c == ',')
+        .next()
+        .map_or(0, |(index, separator)| index + separator.len());
+
+    Some(format!("{}{} ", &input[..start], tag))
+}
+
+// Lists every tagged task under each of its tags, no matter which folder it is in
+#[derive(Default)]
+pub struct TagBrowser {
+    highlight: Highlight,
+}
+
+impl TagBrowser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries(folder: &Folder) -> Vec<(String, TaskRef<'_>)> {
+        let mut entries = vec![];
+        for tag in all(folder) {
+            for found in folder.all_tasks() {
+                if found.task.tags.contains(&tag) {
+                    entries.push((tag.clone(), found));
+                }
+            }
+        }
+        entries
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) {
+        self.highlight.handle_key(key)
+    }
+
+    pub fn selected_task(&self, folder: &Folder) -> Option<(Vec<Id>, Id)> {
+        let entries = Self::entries(folder);
+        let tasks = entries.iter().map(|(_, found)| found).collect::<Vec<_>>();
+        self.highlight.target(&tasks)
+    }
+
+    pub fn render<B: Backend>(
+        &mut self,
+        frame: &mut Frame<B>,
+        chunks: &Chunks,
+        folder: &Folder,
+        scroll: &mut Scroll,
+    ) {
+        let entries = Self::entries(folder);
+        let selected = self.highlight.row(entries.len());
+
+        let list = entries
+            .iter()
+            .enumerate()
+            .map(|(i, (tag, found))| {
+                let mut spans = chips(&BTreeSet::from([tag.clone()]));
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(
+                    found.full_path(),
+                    Style::default().fg(found.task.due_color()),
+                ));
+
+                match i == selected {
+                    true => {
+                        ListItem::new(Line::from(spans)).style(Style::default().bg(Color::DarkGray))
+                    }
+                    false => ListItem::new(Line::from(spans)),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let list = List::new(list).block(
+            Block::default()
+                .title("Tags  <enter> JUMP")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain),
+        );
+
+        frame.render_widget(list, chunks.left_menu());
+
+        if let Some((_, found)) = entries.get(selected) {
+            render_task_details(frame, chunks, found.task, scroll);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Item, Task};
+
+    #[test]
+    fn completes_after_any_separator() {
+        let mut folder = Folder::new();
+        folder.insert(Item::Task(Task {
+            tags: parse("work"),
+            ..Task::default()
+        }));
+
+        assert_eq!(complete(&folder, "w"), Some("work ".to_string()));
+        assert_eq!(complete(&folder, "home,w"), Some("home,work ".to_string()));
+        // A no-break space takes two bytes
+        assert_eq!(
+            complete(&folder, "home\u{a0}w"),
+            Some("home\u{a0}work ".to_string())
+        );
+        assert_eq!(
+            complete(&folder, "x\u{a0}"),
+            Some("x\u{a0}work ".to_string())
+        );
+    }
+}
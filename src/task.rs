@@ -1,20 +1,158 @@
-use std::{collections::VecDeque, fs};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashSet, VecDeque},
+    fmt,
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::Path,
+};
 
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
 use ratatui::{
-    style::Style,
-    widgets::{List, ListItem, Paragraph},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{List, ListItem},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    dates::{format_duration, format_when},
+    format,
+    recur::Recurrence,
+    tags,
+    workspace::Workspace,
 };
-use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default, Copy, Clone)]
 pub enum TaskFile {
     #[default]
     Main,
     Trash,
     Complete,
+    Statuses,
+}
+
+impl TaskFile {
+    pub fn read_or_create<T: Serialize + DeserializeOwned + Default>(
+        &self,
+        workspace: &Workspace,
+    ) -> anyhow::Result<T> {
+        match read_existing(&workspace.path(*self))? {
+            Some(data) => Ok(serde_json::from_str(&data)?),
+            // The file doesn't exist, create it
+            None => {
+                let value = T::default();
+                self.write(workspace, &value)?;
+                Ok(value)
+            }
+        }
+    }
+
+    pub fn write<T: Serialize>(&self, workspace: &Workspace, value: &T) -> anyhow::Result<()> {
+        write_atomic(
+            &workspace.path(*self),
+            serde_json::to_string_pretty(value)?.as_bytes(),
+        )
+    }
+}
+
+// The contents of the file, or None if it doesn't exist yet. Files that exist but can't be
+// read are errors, so they're never mistaken for missing ones and written over
+pub fn read_existing(path: &Path) -> anyhow::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(data) => Ok(Some(data)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(anyhow!("Couldn't read {}: {}", path.display(), e)),
+    }
+}
+
+// Write to a temporary file first and rename it into place,
+// so a crash mid-write can never leave a truncated file behind
+pub fn write_atomic(path: &Path, data: &[u8]) -> anyhow::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+
+    let mut temp = File::create(&temp_path)?;
+    temp.write_all(data)?;
+    temp.sync_all()?;
+
+    fs::rename(&temp_path, path)?;
+
+    // Make sure the rename itself has hit the disk
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+
+    Ok(())
 }
 
+// Identifies a task or folder for good, no matter what it is renamed to or where it is moved.
+// Every new task and folder gets a fresh one
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct Id(Uuid);
+
+impl Default for Id {
+    fn default() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+// Anything that can live inside of a folder
 #[derive(Serialize, Deserialize, Clone)]
+pub enum Item {
+    Task(Task),
+    Folder(Folder),
+}
+
+impl Item {
+    pub fn id(&self) -> Id {
+        match self {
+            Item::Task(task) => task.id,
+            Item::Folder(folder) => folder.id,
+        }
+    }
+
+    // Its own id and those of everything inside it
+    pub fn ids(&self) -> HashSet<Id> {
+        match self {
+            Item::Task(task) => HashSet::from([task.id]),
+            Item::Folder(folder) => folder.ids(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Item::Task(task) => &task.title,
+            Item::Folder(folder) => &folder.name,
+        }
+    }
+
+    // A copy of the item where it and everything inside of it has new ids
+    pub fn duplicate(&self) -> Item {
+        let mut item = self.clone();
+        match &mut item {
+            Item::Task(task) => {
+                task.id = Id::default();
+                // Tracked time stays with the original
+                task.time.clear();
+            }
+            Item::Folder(folder) => folder.renew_ids(),
+        }
+        item
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Status {
     pub status: String,
     pub color: u8,
@@ -29,27 +167,406 @@ impl Default for Status {
     }
 }
 
-impl Status {
-    pub fn to_paragraph(&self) -> Paragraph {
-        Paragraph::new(self.status.clone())
-            .style(Style::new().fg(ratatui::style::Color::Indexed(self.color)))
+// How important a task is, set with the number keys
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    pub fn from_key(key: char) -> Option<Self> {
+        match key {
+            '0' => Some(Priority::None),
+            '1' => Some(Priority::Low),
+            '2' => Some(Priority::Medium),
+            '3' => Some(Priority::High),
+            '4' => Some(Priority::Urgent),
+            _ => None,
+        }
     }
+
+    fn is_none(&self) -> bool {
+        *self == Priority::None
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Priority::None => "no",
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+            Priority::Urgent => "urgent",
+        }
+    }
+
+    // The glyph shown in front of the task in the list
+    pub fn to_span(self) -> Option<Span<'static>> {
+        let (glyph, color) = match self {
+            Priority::None => return None,
+            Priority::Low => ("▽ ", Color::LightBlue),
+            Priority::Medium => ("◇ ", Color::Yellow),
+            Priority::High => ("△ ", Color::LightRed),
+            Priority::Urgent => ("▲ ", Color::Red),
+        };
+        Some(Span::styled(glyph, Style::default().fg(color)))
+    }
+}
+
+// How a folder orders its tasks, manual keeps them where they were put
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    #[default]
+    Manual,
+    Priority,
+    Due,
+    Title,
+    Status,
+    Created,
 }
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Manual => SortMode::Priority,
+            SortMode::Priority => SortMode::Due,
+            SortMode::Due => SortMode::Title,
+            SortMode::Title => SortMode::Status,
+            SortMode::Status => SortMode::Created,
+            SortMode::Created => SortMode::Manual,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortMode::Manual => "manual",
+            SortMode::Priority => "priority",
+            SortMode::Due => "due date",
+            SortMode::Title => "title",
+            SortMode::Status => "status",
+            SortMode::Created => "created",
+        }
+    }
+
+    fn is_manual(&self) -> bool {
+        *self == SortMode::Manual
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
 pub struct Task {
+    #[serde(default)]
+    pub id: Id,
     pub title: String,
     pub task: String,
     pub status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_time: Option<NaiveTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<NaiveTime>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistItem>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "Priority::is_none")]
+    pub priority: Priority,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recur: Option<Recurrence>,
+    // How long the task should take, in minutes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<u32>,
+    // Every run of the timer, the last one has no end while it is running
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub time: Vec<TimeEntry>,
+    // The tasks that have to be finished before this one can start
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by: Vec<Id>,
+    // What happened to the task, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub log: Vec<LogEntry>,
+}
+
+// How many changes a task remembers
+const LOG_LIMIT: usize = 100;
+
+// Totals over the tasks of a folder and every folder below it
+#[derive(Default)]
+pub struct Rollup {
+    pub open: usize,
+    pub total: usize,
+    pub estimated: Duration,
+    pub tracked: Duration,
+    // What is left of the estimates of the open tasks
+    pub remaining: Duration,
+}
+
+impl Rollup {
+    // The short form shown after the folder's row in the list
+    fn suffix(&self) -> Option<String> {
+        let mut parts = vec![];
+        if self.total > 0 {
+            parts.push(format!("{} open", self.open));
+        }
+        match (
+            self.tracked > Duration::zero(),
+            self.estimated > Duration::zero(),
+        ) {
+            (_, true) => parts.push(format!(
+                "{}/{}",
+                format_duration(self.tracked),
+                format_duration(self.estimated)
+            )),
+            (true, false) => parts.push(format_duration(self.tracked)),
+            (false, false) => {}
+        }
+
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
 }
 
-#[derive(Default, serde::Serialize, serde::Deserialize)]
+// One run of a task's timer
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct TimeEntry {
+    pub start: NaiveDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<NaiveDateTime>,
+}
+
+// One change in a task's activity log
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct LogEntry {
+    pub at: NaiveDateTime,
+    pub what: String,
+}
+
+// One step of a task's checklist
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct ChecklistItem {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl Task {
+    // When the task is due, a date without a time is due at the end of that day
+    pub fn due_at(&self) -> Option<NaiveDateTime> {
+        let time = self
+            .due_time
+            .unwrap_or(NaiveTime::from_hms_opt(23, 59, 59).expect("Time should be valid"));
+
+        self.due.map(|date| date.and_time(time))
+    }
+
+    // How many checklist items are done out of how many, if there is a checklist
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        if self.checklist.is_empty() {
+            return None;
+        }
+
+        let done = self.checklist.iter().filter(|item| item.done).count();
+        Some((done, self.checklist.len()))
+    }
+
+    // When the running timer was started, if there is one
+    pub fn running(&self) -> Option<NaiveDateTime> {
+        self.time
+            .last()
+            .filter(|entry| entry.end.is_none())
+            .map(|entry| entry.start)
+    }
+
+    // Stop the running timer, if there is one, and log what it tracked
+    pub fn stop_timer(&mut self, now: NaiveDateTime) {
+        if let Some(entry) = self.time.last_mut().filter(|entry| entry.end.is_none()) {
+            entry.end = Some(now);
+            let spent = now - entry.start;
+            self.log(format!("tracked {}", format_duration(spent)));
+        }
+    }
+
+    pub fn estimate(&self) -> Option<Duration> {
+        self.estimate
+            .map(|minutes| Duration::minutes(minutes as i64))
+    }
+
+    // All the time tracked on the task, counting a running timer up to now
+    pub fn tracked(&self, now: NaiveDateTime) -> Duration {
+        self.time.iter().fold(Duration::zero(), |total, entry| {
+            total + (entry.end.unwrap_or(now) - entry.start)
+        })
+    }
+
+    // The part of the tracked time that falls between `from` and `to`
+    pub fn tracked_between(
+        &self,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        now: NaiveDateTime,
+    ) -> Duration {
+        self.time.iter().fold(Duration::zero(), |total, entry| {
+            let (start, end) = (entry.start.max(from), entry.end.unwrap_or(now).min(to));
+            match end > start {
+                true => total + (end - start),
+                false => total,
+            }
+        })
+    }
+
+    // Note a change in the log and stamp the task as modified
+    pub fn log(&mut self, what: String) {
+        let now = Local::now().naive_local();
+        self.modified = Some(now);
+        self.log.push(LogEntry { at: now, what });
+        if self.log.len() > LOG_LIMIT {
+            self.log.remove(0);
+        }
+    }
+
+    // Log everything that changed since `from`. `done` is whether the status the task has now
+    // counts as finished, reaching one stamps when it was completed
+    pub fn record_changes(&mut self, from: &Task, done: bool) {
+        let mut changes = vec![];
+        if self.title != from.title {
+            changes.push(format!("renamed from {}", from.title));
+        }
+        if self.task != from.task {
+            changes.push("details edited".to_string());
+        }
+        if self.status != from.status {
+            changes.push(format!(
+                "status from {} to {}",
+                from.status.status, self.status.status
+            ));
+        }
+        if self.priority != from.priority {
+            changes.push(match self.priority {
+                Priority::None => "priority cleared".to_string(),
+                priority => format!("priority set to {}", priority.name()),
+            });
+        }
+        if (self.due, self.due_time) != (from.due, from.due_time) {
+            changes.push(match self.due {
+                Some(due) => format!("due {}", format_when(due, self.due_time)),
+                None => "due date cleared".to_string(),
+            });
+        }
+        if (self.start, self.start_time) != (from.start, from.start_time) {
+            changes.push(match self.start {
+                Some(start) => format!("starts {}", format_when(start, self.start_time)),
+                None => "start date cleared".to_string(),
+            });
+        }
+        if self.tags != from.tags {
+            changes.push(match self.tags.is_empty() {
+                true => "tags cleared".to_string(),
+                false => format!(
+                    "tagged {}",
+                    self.tags.iter().cloned().collect::<Vec<_>>().join(", ")
+                ),
+            });
+        }
+        if self.estimate != from.estimate {
+            changes.push(match self.estimate() {
+                Some(estimate) => format!("estimated at {}", format_duration(estimate)),
+                None => "estimate cleared".to_string(),
+            });
+        }
+        if self.recur != from.recur {
+            changes.push(match &self.recur {
+                Some(rule) => format!("repeats {}", rule),
+                None => "no longer repeats".to_string(),
+            });
+        }
+        if self.checklist != from.checklist {
+            changes.push(match self.progress() {
+                Some((done, total)) => format!("checklist {}/{}", done, total),
+                None => "checklist cleared".to_string(),
+            });
+        }
+
+        for change in changes {
+            self.log(change);
+        }
+
+        if self.status != from.status {
+            self.completed = done.then(|| Local::now().naive_local());
+        }
+    }
+
+    pub fn due_color(&self) -> Color {
+        let now = Local::now().naive_local();
+
+        match self.due_at() {
+            Some(due) if due < now => Color::LightRed,
+            Some(due) if due - now < Duration::days(1) => Color::LightYellow,
+            _ => Color::LightGreen,
+        }
+    }
+}
+
+// A task found somewhere in the folder tree
+pub struct TaskRef<'a> {
+    // The names of the folders leading to the task, for showing it
+    pub path: Vec<String>,
+    // The ids of the same folders, for finding it again
+    pub folders: Vec<Id>,
+    pub task: &'a Task,
+}
+
+impl TaskRef<'_> {
+    pub fn full_path(&self) -> String {
+        let mut path = self.path.clone();
+        path.push(self.task.title.clone());
+        path.join("/")
+    }
+
+    pub fn to_list_item(&self) -> ListItem<'_> {
+        ListItem::new(Line::from(vec![
+            Span::styled(self.full_path(), Style::default().fg(Color::LightGreen)),
+            Span::raw(" "),
+            Span::styled(
+                self.task.status.status.clone(),
+                Style::default().fg(Color::Indexed(self.task.status.color)),
+            ),
+        ]))
+    }
+}
+
+#[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Folder {
+    #[serde(default)]
+    pub id: Id,
     pub name: String,
+    #[serde(default, skip_serializing_if = "SortMode::is_manual")]
+    pub sort: SortMode,
     tasks: Vec<Task>,
     folders: Vec<Folder>,
+    // Where the selection is in the list as shown, which the sort mode can put in another order
     #[serde(skip_serializing, default)]
     selected: usize,
+    // The indices of the items in the order they are shown, empty while they show as stored.
+    // Sorting only changes this, the stored order stays the one made by hand
+    #[serde(skip)]
+    view: Vec<usize>,
+    // Items picked out for bulk actions
+    #[serde(skip)]
+    marked: HashSet<Id>,
 }
 
 impl Folder {
@@ -57,14 +574,6 @@ impl Folder {
         Self::default()
     }
 
-    pub fn new_task(&mut self, task: Task) -> &mut Task {
-        self.tasks.push(task);
-
-        let task_len = self.tasks.len() - 1;
-
-        self.tasks.get_mut(task_len).expect("Task should exist")
-    }
-
     pub fn new_folder(&mut self, name: String) -> &mut Folder {
         let mut folder = Folder::new();
         folder.name = name;
@@ -77,117 +586,563 @@ impl Folder {
             .expect("Folder should exist")
     }
 
-    pub fn read_or_create() -> anyhow::Result<Self> {
-        if let Some(dirs) = directories::UserDirs::new() {
-            let home_dir = dirs.home_dir();
-
-            let tasks_dir = home_dir.join(".rtasks");
+    // Read the workspace's file, which has to be there already
+    pub fn read(workspace: &Workspace) -> anyhow::Result<Self> {
+        let data = read_existing(&workspace.file)?
+            .ok_or_else(|| anyhow!("{} doesn't exist", workspace.file.display()))?;
+        format::parse(&data)
+    }
 
-            // Ensure the directory exists
-            #[allow(clippy::single_match)]
-            match fs::create_dir(tasks_dir.clone()).is_ok() {
-                true => {}
-                false => {}
+    pub fn read_or_create(workspace: &Workspace) -> anyhow::Result<Self> {
+        match read_existing(&workspace.file)? {
+            Some(data) => format::parse(&data),
+            // The file doesn't exist, create it
+            None => {
+                let folder = Folder::default();
+                folder.save(workspace)?;
+                Ok(folder)
             }
+        }
+    }
 
-            // Try to read the file
-            if let Ok(data) = fs::read_to_string(tasks_dir.join("tasks.json")) {
-                Ok(serde_json::from_str(&data)?)
-            } else {
-                // The file doesn't exist, create it
-                let folder = Folder::default();
+    // Write this to the workspace's file
+    pub fn save(&self, workspace: &Workspace) -> anyhow::Result<()> {
+        write_atomic(&workspace.file, format::to_string(self)?.as_bytes())
+    }
+
+    // Remove the selected item from the folder, handing it back to the caller
+    pub fn delete_selected(&mut self) -> Option<Item> {
+        let item = self.remove_at(self.stored_at(self.selected)?)?;
 
-                let folder_json = serde_json::to_string_pretty(&folder)?;
+        if self.selected > 0 {
+            self.selected -= 1;
+        }
 
-                fs::write(tasks_dir.join("tasks.json"), folder_json)?;
+        Some(item)
+    }
 
-                Ok(folder)
+    // Add an item to the end of its list, returning the index it ended up at
+    pub fn insert(&mut self, item: Item) -> usize {
+        let index = match item {
+            Item::Task(task) => {
+                self.tasks.push(task);
+                self.folders.len() + self.tasks.len() - 1
+            }
+            Item::Folder(folder) => {
+                self.folders.push(folder);
+                self.folders.len() - 1
+            }
+        };
+        self.view_inserted(index);
+        index
+    }
+
+    // Indices count the folders first, then the tasks, matching the order of the list
+    pub fn insert_at(&mut self, index: usize, item: Item) {
+        let index = match item {
+            Item::Task(task) => {
+                let index = index
+                    .saturating_sub(self.folders.len())
+                    .min(self.tasks.len());
+                self.tasks.insert(index, task);
+                self.folders.len() + index
+            }
+            Item::Folder(folder) => {
+                let index = index.min(self.folders.len());
+                self.folders.insert(index, folder);
+                index
             }
+        };
+        self.view_inserted(index);
+        self.adjust_selected(0);
+    }
+
+    pub fn remove_at(&mut self, index: usize) -> Option<Item> {
+        self.view_removed(index);
+        let item = if index < self.folders.len() {
+            Item::Folder(self.folders.remove(index))
+        } else if index - self.folders.len() < self.tasks.len() {
+            Item::Task(self.tasks.remove(index - self.folders.len()))
         } else {
-            Err(anyhow!("Failed to find user home directory"))
+            return None;
+        };
+
+        self.adjust_selected(0);
+        Some(item)
+    }
+
+    fn len(&self) -> usize {
+        self.folders.len() + self.tasks.len()
+    }
+
+    // The stored indices of the items in the order they are shown
+    fn order(&self) -> Vec<usize> {
+        match self.view.len() == self.len() {
+            true => self.view.clone(),
+            false => (0..self.len()).collect(),
         }
     }
 
-    // Write this to the specified file
-    pub fn save(&self) -> anyhow::Result<()> {
-        if let Some(dirs) = directories::UserDirs::new() {
-            let home_dir = dirs.home_dir();
+    // Where the item shown at the row is stored
+    fn stored_at(&self, row: usize) -> Option<usize> {
+        self.order().get(row).copied()
+    }
 
-            let tasks_dir = home_dir.join(".rtasks");
+    // Keep the view in step with an item added at the index. It shows at the end of the
+    // folders or the tasks until the next sort
+    fn view_inserted(&mut self, index: usize) {
+        if self.view.len() + 1 != self.len() {
+            self.view.clear();
+            return;
+        }
 
-            fs::write(
-                tasks_dir.join("tasks.json"),
-                serde_json::to_string_pretty(&self)?,
-            )?;
+        for stored in &mut self.view {
+            if *stored >= index {
+                *stored += 1;
+            }
+        }
+        match index < self.folders.len() {
+            true => self.view.insert(self.folders.len() - 1, index),
+            false => self.view.push(index),
         }
-        Ok(())
     }
 
-    pub fn delete_selected(&mut self) {
-        if self.folders.is_empty() && self.tasks.is_empty() {
+    fn view_removed(&mut self, index: usize) {
+        if self.view.len() != self.len() {
+            self.view.clear();
             return;
         }
 
-        if self.selected < self.folders.len() {
-            self.folders.remove(self.selected);
-            if self.selected > 0 {
-                self.selected -= 1;
+        self.view.retain(|stored| *stored != index);
+        for stored in &mut self.view {
+            if *stored > index {
+                *stored -= 1;
             }
-        } else {
-            self.tasks.remove(self.selected - self.folders.len());
-            if self.selected > 0 {
-                self.selected -= 1;
+        }
+    }
+
+    pub fn selected_id(&self) -> Option<Id> {
+        self.id_at(self.stored_at(self.selected)?)
+    }
+
+    pub fn id_at(&self, index: usize) -> Option<Id> {
+        match self.folders.get(index) {
+            Some(folder) => Some(folder.id),
+            None => self
+                .tasks
+                .get(index - self.folders.len())
+                .map(|task| task.id),
+        }
+    }
+
+    pub fn toggle_mark(&mut self) {
+        if let Some(id) = self.selected_id() {
+            if !self.marked.remove(&id) {
+                self.marked.insert(id);
             }
         }
     }
 
-    pub fn get_folder(&mut self, path: impl Into<VecDeque<String>>) -> anyhow::Result<&mut Folder> {
+    // Mark everything, or clear the marks if everything already is
+    pub fn toggle_mark_all(&mut self) {
+        let all = (0..self.folders.len() + self.tasks.len())
+            .filter_map(|index| self.id_at(index))
+            .collect::<HashSet<_>>();
+
+        self.marked = match all.is_subset(&self.marked) {
+            true => HashSet::new(),
+            false => all,
+        };
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    // The marked items, in list order
+    pub fn marked(&self) -> Vec<Id> {
+        self.order()
+            .into_iter()
+            .filter_map(|index| self.id_at(index))
+            .filter(|id| self.marked.contains(id))
+            .collect()
+    }
+
+    // What a bulk action applies to, the marked items, or else the selected one
+    pub fn marked_or_selected(&self) -> Vec<Id> {
+        match self.marked() {
+            marked if marked.is_empty() => self.selected_id().into_iter().collect(),
+            marked => marked,
+        }
+    }
+
+    pub fn selected_item(&self) -> Option<Item> {
+        self.item_at(self.stored_at(self.selected)?)
+    }
+
+    pub fn item_at(&self, index: usize) -> Option<Item> {
+        match self.folders.get(index) {
+            Some(folder) => Some(Item::Folder(folder.clone())),
+            None => self
+                .tasks
+                .get(index - self.folders.len())
+                .cloned()
+                .map(Item::Task),
+        }
+    }
+
+    // Where the selected item would go when moved by dist, staying among the folders or tasks.
+    // Only used while sorting by hand, when the list shows the stored order
+    pub fn reorder_target(&self, dist: i32) -> Option<usize> {
+        let range = match self.selected < self.folders.len() {
+            true => 0..self.folders.len(),
+            false => self.folders.len()..self.folders.len() + self.tasks.len(),
+        };
+
+        let target = usize::try_from(self.selected as i32 + dist).ok()?;
+        range.contains(&target).then_some(target)
+    }
+
+    // Like get_folder, but creates any folders along the path that don't exist
+    pub fn get_or_create_folder(&mut self, path: impl Into<VecDeque<String>>) -> &mut Folder {
+        let mut path = path.into();
+        match path.pop_front() {
+            Some(item) => {
+                let index = match self.folders.iter().position(|folder| folder.name == item) {
+                    Some(index) => index,
+                    None => {
+                        self.new_folder(item);
+                        self.folders.len() - 1
+                    }
+                };
+                self.folders[index].get_or_create_folder(path)
+            }
+            None => self,
+        }
+    }
+
+    // Follow a path of folder ids down from this folder
+    pub fn get_folder(&mut self, path: impl Into<VecDeque<Id>>) -> anyhow::Result<&mut Folder> {
         let mut path = path.into();
-        if let Some(item) = path.pop_front() {
+        if let Some(id) = path.pop_front() {
             for folder in &mut self.folders {
-                if folder.name == item {
+                if folder.id == id {
                     return folder.get_folder(path);
                 }
             }
-            Err(anyhow!("Folder with name {} doesn't exist", item))
+            Err(anyhow!("Folder with id {} doesn't exist", id))
         } else {
             Ok(self)
         }
     }
 
-    pub fn get_selected_task(&mut self) -> Option<&mut Task> {
-        if self.folders.is_empty() && self.tasks.is_empty() {
+    // The ids of the folders leading from this one to the folder with the id, wherever it is
+    pub fn path_to(&self, id: Id) -> Option<Vec<Id>> {
+        if self.id == id {
+            return Some(vec![]);
+        }
+
+        self.folders.iter().find_map(|folder| {
+            let mut path = folder.path_to(id)?;
+            path.insert(0, folder.id);
+            Some(path)
+        })
+    }
+
+    // The names of the folders along a path of ids, as far as it can be followed
+    pub fn path_names(&self, path: &[Id]) -> Vec<String> {
+        let mut names = vec![];
+        let mut folder = self;
+        for id in path {
+            let Some(next) = folder.folders.iter().find(|f| f.id == *id) else {
+                break;
+            };
+            names.push(next.name.clone());
+            folder = next;
+        }
+        names
+    }
+
+    pub fn find_folder(&mut self, id: Id) -> Option<&mut Folder> {
+        if self.id == id {
+            return Some(self);
+        }
+
+        self.folders
+            .iter_mut()
+            .find_map(|folder| folder.find_folder(id))
+    }
+
+    pub fn find_task(&mut self, id: Id) -> Option<&mut Task> {
+        match self.tasks.iter().position(|task| task.id == id) {
+            Some(index) => self.tasks.get_mut(index),
+            None => self
+                .folders
+                .iter_mut()
+                .find_map(|folder| folder.find_task(id)),
+        }
+    }
+
+    // Where the item with the id is in this folder's list
+    pub fn position(&self, id: Id) -> Option<usize> {
+        match self.folders.iter().position(|folder| folder.id == id) {
+            Some(index) => Some(index),
+            None => self
+                .tasks
+                .iter()
+                .position(|task| task.id == id)
+                .map(|index| self.folders.len() + index),
+        }
+    }
+
+    pub fn remove(&mut self, id: Id) -> Option<Item> {
+        self.remove_at(self.position(id)?)
+    }
+
+    // The id of the folder directly holding the item with the id
+    pub fn parent_of(&self, id: Id) -> Option<Id> {
+        match self.position(id) {
+            Some(_) => Some(self.id),
+            None => self.folders.iter().find_map(|folder| folder.parent_of(id)),
+        }
+    }
+
+    // Take the item out of the folder `from` and put it into `to`, at the index or the end of
+    // its list. Returns where it ended up, or None if it can't go there, like a folder into itself
+    pub fn move_item(&mut self, id: Id, from: Id, to: Id, index: Option<usize>) -> Option<usize> {
+        if self
+            .find_folder(id)
+            .is_some_and(|folder| folder.find_folder(to).is_some())
+        {
             return None;
         }
+        self.find_folder(to)?;
 
-        if self.selected < self.folders.len() {
-            None
-        } else if let Some(task) = self.tasks.get_mut(self.selected - self.folders.len()) {
-            Some(task)
-        } else {
-            None
+        let item = self.find_folder(from)?.remove(id)?;
+        let target = self.find_folder(to)?;
+        match index {
+            Some(index) => target.insert_at(index, item),
+            None => {
+                target.insert(item);
+            }
+        }
+        target.position(id)
+    }
+
+    // Show the items of this folder and every one below it in the order their sort modes ask
+    // for, keeping the same item selected. Statuses sort by their rank
+    pub fn sort_all(&mut self, status_rank: &dyn Fn(&Status) -> usize) {
+        let selected = self.selected_id();
+
+        let mut folders = (0..self.folders.len()).collect::<Vec<_>>();
+        let mut tasks = (0..self.tasks.len()).collect::<Vec<_>>();
+        let task = |index: &usize| &self.tasks[*index];
+        match self.sort {
+            SortMode::Manual => {}
+            SortMode::Priority => tasks.sort_by_key(|index| Reverse(task(index).priority)),
+            SortMode::Due => tasks.sort_by_key(|index| {
+                let due = task(index).due_at();
+                (due.is_none(), due)
+            }),
+            SortMode::Title => {
+                folders.sort_by_key(|index| self.folders[*index].name.to_lowercase());
+                tasks.sort_by_key(|index| task(index).title.to_lowercase());
+            }
+            SortMode::Status => tasks.sort_by_key(|index| status_rank(&task(index).status)),
+            SortMode::Created => tasks.sort_by_key(|index| task(index).created),
+        }
+
+        self.view = match self.sort {
+            SortMode::Manual => vec![],
+            _ => folders
+                .into_iter()
+                .chain(tasks.into_iter().map(|index| index + self.folders.len()))
+                .collect(),
+        };
+
+        if let Some(id) = selected {
+            self.select(id);
+        }
+
+        for folder in &mut self.folders {
+            folder.sort_all(status_rank);
+        }
+    }
+
+    // Add up the estimates, tracked time and open tasks of this folder and the ones below it
+    pub fn rollup(&self, now: NaiveDateTime, is_done: &dyn Fn(&Status) -> bool) -> Rollup {
+        let mut rollup = Rollup::default();
+        for found in self.all_tasks() {
+            let task = found.task;
+            let (estimate, tracked) = (task.estimate().unwrap_or_default(), task.tracked(now));
+
+            rollup.total += 1;
+            rollup.estimated += estimate;
+            rollup.tracked += tracked;
+            if !is_done(&task.status) {
+                rollup.open += 1;
+                if estimate > tracked {
+                    rollup.remaining += estimate - tracked;
+                }
+            }
+        }
+        rollup
+    }
+
+    // The task whose timer is running, there is only ever one
+    pub fn running_task(&self) -> Option<TaskRef<'_>> {
+        self.all_tasks()
+            .into_iter()
+            .find(|found| found.task.running().is_some())
+    }
+
+    // Every folder in the tree, starting with this one, along with its path
+    pub fn all_folders(&self) -> Vec<(Id, String)> {
+        let mut folders = vec![(self.id, "/".to_string())];
+        self.collect_folders("", &mut folders);
+        folders
+    }
+
+    // The path of the folder with the id, written the way the move picker shows it
+    pub fn path_string(&self, id: Id) -> String {
+        match self.path_to(id) {
+            Some(path) => format!("/{}", self.path_names(&path).join("/")),
+            None => "?".to_string(),
+        }
+    }
+
+    fn collect_folders(&self, path: &str, out: &mut Vec<(Id, String)>) {
+        for folder in &self.folders {
+            let path = format!("{}/{}", path, folder.name);
+            out.push((folder.id, path.clone()));
+            folder.collect_folders(&path, out);
+        }
+    }
+
+    fn renew_ids(&mut self) {
+        self.id = Id::default();
+        for task in &mut self.tasks {
+            task.id = Id::default();
+            task.time.clear();
+        }
+        for folder in &mut self.folders {
+            folder.renew_ids();
+        }
+    }
+
+    // Highlight the item with the id, if it is in this folder
+    pub fn select(&mut self, id: Id) {
+        if let Some(index) = self.position(id) {
+            if let Some(row) = self.order().iter().position(|stored| *stored == index) {
+                self.selected = row;
+            }
+        }
+    }
+
+    pub fn get_selected_task(&mut self) -> Option<&mut Task> {
+        let index = self.stored_at(self.selected)?;
+        match index < self.folders.len() {
+            true => None,
+            false => self.tasks.get_mut(index - self.folders.len()),
         }
     }
 
     pub fn adjust_selected(&mut self, dist: i32) {
-        let max = self.tasks.len().max(0) as i32 + self.folders.len().max(0) as i32 - 1;
+        let max = (self.tasks.len() as i32 + self.folders.len() as i32 - 1).max(0);
 
         self.selected = (self.selected as i32 + dist).clamp(0, max).unsigned_abs() as usize;
     }
 
     pub fn get_selected_folder(&mut self) -> Option<&mut Folder> {
-        if self.selected >= self.folders.len() {
-            return None;
+        let index = self.stored_at(self.selected)?;
+        self.folders.get_mut(index)
+    }
+
+    // Every id in this folder and below it, its own included
+    pub fn ids(&self) -> HashSet<Id> {
+        let mut ids = self
+            .all_folders()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<HashSet<_>>();
+        ids.extend(self.all_tasks().iter().map(|found| found.task.id));
+        ids
+    }
+
+    // Bring in what was added to the disk's copy of this tree. Items are matched by id wherever
+    // they are, so everything this tree has or let go of (`removed`) stays the way it is here
+    // and only the rest goes into the folder it is in on disk
+    pub fn merge_from(&mut self, disk: &Folder, removed: &HashSet<Id>) {
+        let mut known = self.ids();
+        known.extend(removed);
+        self.add_missing(self.id, disk, &known);
+    }
+
+    fn add_missing(&mut self, into: Id, disk: &Folder, known: &HashSet<Id>) {
+        for folder in &disk.folders {
+            if !known.contains(&folder.id) {
+                if let Some(target) = self.find_folder(into) {
+                    target.insert(Item::Folder(Folder {
+                        id: folder.id,
+                        name: folder.name.clone(),
+                        sort: folder.sort,
+                        ..Folder::default()
+                    }));
+                }
+            }
+            // A folder that is here takes in what was added below it, wherever it was moved
+            self.add_missing(folder.id, folder, known);
         }
 
-        Some(&mut self.folders[self.selected])
+        if let Some(target) = self.find_folder(into) {
+            for task in disk.tasks.iter().filter(|task| !known.contains(&task.id)) {
+                target.insert(Item::Task(task.clone()));
+            }
+        }
     }
 
-    pub fn as_list_widget(&mut self) -> List {
+    // Collect every task in this folder and its subfolders, along with where to find it
+    pub fn all_tasks(&self) -> Vec<TaskRef<'_>> {
+        let mut tasks = vec![];
+        self.collect_tasks(&mut vec![], &mut vec![], &mut tasks);
+        tasks
+    }
+
+    fn collect_tasks<'a>(
+        &'a self,
+        path: &mut Vec<String>,
+        folders: &mut Vec<Id>,
+        out: &mut Vec<TaskRef<'a>>,
+    ) {
+        for task in &self.tasks {
+            out.push(TaskRef {
+                path: path.clone(),
+                folders: folders.clone(),
+                task,
+            });
+        }
+
+        for folder in &self.folders {
+            path.push(folder.name.clone());
+            folders.push(folder.id);
+            folder.collect_tasks(path, folders, out);
+            folders.pop();
+            path.pop();
+        }
+    }
+
+    // Tasks in `blocked` are dimmed behind a lock, folders show what their tasks add up to
+    pub fn as_list_widget(
+        &mut self,
+        blocked: &HashSet<Id>,
+        is_done: &dyn Fn(&Status) -> bool,
+    ) -> List<'_> {
+        let now = Local::now().naive_local();
+        let selected = self.stored_at(self.selected);
         let mut list = vec![];
         // Add the folders to the list
         for folder in &self.folders {
-            let style = if list.len() == self.selected {
+            let style = if Some(list.len()) == selected {
                 Style::default()
                     .fg(ratatui::style::Color::LightCyan)
                     .bg(ratatui::style::Color::DarkGray)
@@ -195,22 +1150,183 @@ impl Folder {
                 Style::default().fg(ratatui::style::Color::LightCyan)
             };
 
-            list.push(ListItem::new(folder.name.clone()).style(style));
+            let mut spans = vec![Span::raw(folder.name.clone())];
+            if let Some(suffix) = folder.rollup(now, is_done).suffix() {
+                spans.push(Span::styled(
+                    format!(" {}", suffix),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+
+            list.push(self.list_item(folder.id, spans, style));
         }
 
         // Add the tasks to the list
         for task in &self.tasks {
-            let style = if list.len() == self.selected {
+            let style = if Some(list.len()) == selected {
                 Style::default()
-                    .fg(ratatui::style::Color::LightGreen)
+                    .fg(task.due_color())
                     .bg(ratatui::style::Color::DarkGray)
             } else {
-                Style::default().fg(ratatui::style::Color::LightGreen)
+                Style::default().fg(task.due_color())
+            };
+
+            let mut spans = task.priority.to_span().into_iter().collect::<Vec<_>>();
+            let style = match blocked.contains(&task.id) {
+                true => {
+                    spans.insert(0, Span::raw("🔒 "));
+                    style.fg(Color::Gray).add_modifier(Modifier::DIM)
+                }
+                false => style,
             };
+            spans.push(Span::raw(match task.progress() {
+                Some((done, total)) => format!("{} [{}/{}]", task.title, done, total),
+                None => task.title.clone(),
+            }));
+            if task.recur.is_some() {
+                spans.push(Span::styled(" ↻", Style::default().fg(Color::LightMagenta)));
+            }
+            spans.extend(tracked_span(task.tracked(now), task.running().is_some()));
+            spans.extend(tags::chips(&task.tags));
 
-            list.push(ListItem::new(task.title.clone()).style(style));
+            list.push(self.list_item(task.id, spans, style));
         }
 
+        // Then show them in the order of the sort mode
+        let mut list = list.into_iter().map(Some).collect::<Vec<_>>();
+        let list = self
+            .order()
+            .into_iter()
+            .filter_map(|index| list[index].take())
+            .collect::<Vec<_>>();
         List::new(list)
     }
+
+    // Marked rows stand out with a bullet and bold text
+    fn list_item(&self, id: Id, mut spans: Vec<Span<'static>>, style: Style) -> ListItem<'static> {
+        match self.marked.contains(&id) {
+            true => {
+                spans.insert(0, Span::raw("● "));
+                ListItem::new(Line::from(spans))
+                    .style(style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED))
+            }
+            false => ListItem::new(Line::from(spans)).style(style),
+        }
+    }
+}
+
+// The tracked time after a row in the list, if any was tracked
+fn tracked_span(tracked: Duration, running: bool) -> Option<Span<'static>> {
+    match (tracked > Duration::zero(), running) {
+        (_, true) => Some(Span::styled(
+            format!(" ⏱ {}", format_duration(tracked)),
+            Style::default().fg(Color::LightYellow),
+        )),
+        (true, false) => Some(Span::styled(
+            format!(" {}", format_duration(tracked)),
+            Style::default().fg(Color::DarkGray),
+        )),
+        (false, false) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str) -> Item {
+        Item::Task(Task {
+            title: title.to_string(),
+            ..Task::default()
+        })
+    }
+
+    // The titles in the order the list shows them
+    fn shown(folder: &mut Folder) -> Vec<String> {
+        folder.toggle_mark_all();
+        let ids = folder.marked();
+        folder.clear_marks();
+        ids.into_iter()
+            .filter_map(|id| folder.find_task(id).map(|task| task.title.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn sorting_keeps_the_manual_order() {
+        let mut folder = Folder::new();
+        for title in ["c", "a", "b"] {
+            folder.insert(task(title));
+        }
+
+        folder.sort = SortMode::Title;
+        folder.sort_all(&|_| 0);
+        assert_eq!(shown(&mut folder), ["a", "b", "c"]);
+
+        // The selection follows the rows as shown
+        folder.adjust_selected(-3);
+        folder.adjust_selected(1);
+        assert_eq!(folder.get_selected_task().unwrap().title, "b");
+
+        folder.sort = SortMode::Manual;
+        folder.sort_all(&|_| 0);
+        assert_eq!(shown(&mut folder), ["c", "a", "b"]);
+        assert_eq!(folder.get_selected_task().unwrap().title, "b");
+    }
+
+    #[test]
+    fn view_follows_inserts_and_removes() {
+        let mut folder = Folder::new();
+        for title in ["c", "a", "b"] {
+            folder.insert(task(title));
+        }
+        folder.sort = SortMode::Title;
+        folder.sort_all(&|_| 0);
+
+        // New items show at the end until the next sort
+        folder.insert_at(0, task("d"));
+        assert_eq!(shown(&mut folder), ["a", "b", "c", "d"]);
+        folder.insert(task("0"));
+        assert_eq!(shown(&mut folder), ["a", "b", "c", "d", "0"]);
+
+        // c stayed selected through the sort
+        assert_eq!(folder.get_selected_task().unwrap().title, "c");
+        folder.delete_selected();
+        assert_eq!(shown(&mut folder), ["a", "b", "d", "0"]);
+
+        folder.sort_all(&|_| 0);
+        assert_eq!(shown(&mut folder), ["0", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn merging_keeps_local_moves_and_deletes() {
+        let mut base = Folder::new();
+        let a = base.new_folder("a".to_string()).id;
+        let b = base.new_folder("b".to_string()).id;
+        let (moved, deleted) = (task("moved"), task("deleted"));
+        let (moved_id, deleted_id) = (moved.id(), deleted.id());
+        base.find_folder(a).unwrap().insert(moved);
+        base.find_folder(a).unwrap().insert(deleted);
+
+        // Here one task moved to b and the other was deleted
+        let mut ours = base.clone();
+        ours.move_item(moved_id, a, b, None);
+        ours.find_folder(a).unwrap().remove(deleted_id);
+
+        // On disk a task was added to a, and a folder with a task in it
+        let mut disk = base.clone();
+        disk.find_folder(a).unwrap().insert(task("added"));
+        let c = disk.find_folder(a).unwrap().new_folder("c".to_string()).id;
+        disk.find_folder(c).unwrap().insert(task("nested"));
+
+        ours.merge_from(&disk, &HashSet::from([deleted_id]));
+
+        assert!(ours.parent_of(moved_id) == Some(b));
+        assert!(ours.find_task(deleted_id).is_none());
+        assert_eq!(shown(ours.find_folder(a).unwrap()), ["added"]);
+        assert_eq!(shown(ours.find_folder(c).unwrap()), ["nested"]);
+        assert!(ours.parent_of(c) == Some(a));
+        // Every id is in the tree once
+        let count = ours.all_folders().len() + ours.all_tasks().len();
+        assert_eq!(ours.ids().len(), count);
+    }
 }
@@ -0,0 +1,201 @@
+use chrono::{Datelike, Days, Duration, Local, NaiveDate, NaiveDateTime};
+use crossterm::event::KeyCode;
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{
+    dates::format_duration,
+    store::Store,
+    task::{Folder, TaskRef},
+    Chunks,
+};
+
+// Reports the time tracked on each task over a day or a week
+pub struct Timesheet {
+    pub focused: NaiveDate,
+    pub weekly: bool,
+}
+
+impl Default for Timesheet {
+    fn default() -> Self {
+        Self {
+            focused: Local::now().date_naive(),
+            weekly: true,
+        }
+    }
+}
+
+impl Timesheet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) {
+        let step = Days::new(if self.weekly { 7 } else { 1 });
+        let focused = match key {
+            KeyCode::Left => self.focused.checked_sub_days(step),
+            KeyCode::Right => self.focused.checked_add_days(step),
+            KeyCode::Char('t') => Some(Local::now().date_naive()),
+            KeyCode::Char('w') => {
+                self.weekly = !self.weekly;
+                None
+            }
+            _ => None,
+        };
+
+        if let Some(focused) = focused {
+            self.focused = focused;
+        }
+    }
+
+    // The days the report covers, a week starts on monday
+    fn days(&self) -> Vec<NaiveDate> {
+        match self.weekly {
+            true => {
+                let monday =
+                    self.focused - Days::new(self.focused.weekday().num_days_from_monday() as u64);
+                (0..7).map(|day| monday + Days::new(day)).collect()
+            }
+            false => vec![self.focused],
+        }
+    }
+
+    pub fn render<B: Backend>(
+        &self,
+        frame: &mut Frame<B>,
+        chunks: &Chunks,
+        folder: &Folder,
+        complete: &Store,
+    ) {
+        let now = Local::now().naive_local();
+        let days = self.days();
+        let (from, to) = (
+            start_of(days[0]),
+            start_of(days[days.len() - 1]) + Duration::days(1),
+        );
+
+        // Finished tasks count too, their time was still spent
+        let mut tasks = folder.all_tasks();
+        tasks.extend(complete.all_tasks());
+
+        let mut rows = tasks
+            .iter()
+            .map(|found| (found, found.task.tracked_between(from, to, now)))
+            .filter(|(_, tracked)| *tracked > Duration::zero())
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|(_, tracked)| -*tracked);
+        let total = rows
+            .iter()
+            .fold(Duration::zero(), |total, (_, tracked)| total + *tracked);
+
+        let list = rows
+            .iter()
+            .map(|(found, tracked)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:>8}  ", format_duration(*tracked)),
+                        Style::default().fg(Color::LightYellow),
+                    ),
+                    Span::styled(found.full_path(), Style::default().fg(Color::LightGreen)),
+                ]))
+            })
+            .collect::<Vec<_>>();
+
+        let period = match self.weekly {
+            true => format!("Week of {}", days[0].format("%Y-%m-%d")),
+            false => self.focused.format("%a %Y-%m-%d").to_string(),
+        };
+        let list = List::new(list).block(
+            Block::default()
+                .title(format!(
+                    "{}  {}  <w> DAY/WEEK",
+                    period,
+                    format_duration(total)
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain),
+        );
+
+        frame.render_widget(list, chunks.left_menu());
+
+        let details = match self.weekly {
+            true => by_day(&tasks, &days, now),
+            false => entries(&tasks, from, to, now),
+        };
+        let details = Paragraph::new(details).block(
+            Block::default()
+                .title(match self.weekly {
+                    true => "By Day",
+                    false => "Entries",
+                })
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double),
+        );
+
+        frame.render_widget(details, chunks.right_menu());
+    }
+}
+
+fn start_of(day: NaiveDate) -> NaiveDateTime {
+    day.and_hms_opt(0, 0, 0).expect("Midnight should exist")
+}
+
+// The total of each day of the week, with a bar to compare them
+fn by_day(tasks: &[TaskRef], days: &[NaiveDate], now: NaiveDateTime) -> Vec<Line<'static>> {
+    days.iter()
+        .map(|day| {
+            let (from, to) = (start_of(*day), start_of(*day) + Duration::days(1));
+            let tracked = tasks.iter().fold(Duration::zero(), |total, found| {
+                total + found.task.tracked_between(from, to, now)
+            });
+
+            // A block for every half hour
+            let bar = "█".repeat((tracked.num_minutes() / 30) as usize);
+            Line::from(vec![
+                Span::raw(format!(
+                    "{}  {:>8}  ",
+                    day.format("%a %m-%d"),
+                    format_duration(tracked)
+                )),
+                Span::styled(bar, Style::default().fg(Color::LightBlue)),
+            ])
+        })
+        .collect()
+}
+
+// Every timer run of the day, in the order they started
+fn entries(
+    tasks: &[TaskRef],
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+    now: NaiveDateTime,
+) -> Vec<Line<'static>> {
+    let mut entries = tasks
+        .iter()
+        .flat_map(|found| {
+            found
+                .task
+                .time
+                .iter()
+                .filter(move |entry| entry.start < to && entry.end.unwrap_or(now) > from)
+                .map(move |entry| (entry.start, entry.end, found.full_path()))
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|(start, ..)| *start);
+
+    entries
+        .into_iter()
+        .map(|(start, end, name)| {
+            let end = match end {
+                Some(end) => end.format("%H:%M").to_string(),
+                None => "now".to_string(),
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{}-{:<5}  ", start.format("%H:%M"), end),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(name),
+            ])
+        })
+        .collect()
+}
@@ -0,0 +1,153 @@
+use std::{env, fs, path::PathBuf, time::SystemTime};
+
+use ratatui::{prelude::*, widgets::*};
+
+use crate::task::TaskFile;
+
+pub const DEFAULT: &str = "default";
+
+// Where one set of task files lives
+pub struct Workspace {
+    pub name: String,
+    // The main task file, the other task files sit next to it
+    pub file: PathBuf,
+}
+
+impl Workspace {
+    // Pick the workspace to start in. The flags win over RTASKS_FILE, --file over --workspace
+    pub fn resolve(file: Option<PathBuf>, name: Option<String>) -> anyhow::Result<Self> {
+        if let Some(file) = file {
+            return Self::from_file(file);
+        }
+        if let Some(name) = name {
+            return Self::named(&name);
+        }
+        if let Some(file) = env::var_os("RTASKS_FILE") {
+            return Self::from_file(PathBuf::from(file));
+        }
+
+        Self::named(DEFAULT)
+    }
+
+    pub fn from_file(file: PathBuf) -> anyhow::Result<Self> {
+        if let Some(dir) = file.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+
+        Ok(Self {
+            name: file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| DEFAULT.to_string()),
+            file,
+        })
+    }
+
+    // The default workspace lives in the data directory itself, the rest in workspaces/<name>
+    pub fn named(name: &str) -> anyhow::Result<Self> {
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(anyhow!("{:?} isn't a valid workspace name", name));
+        }
+
+        let dir = match name {
+            DEFAULT => data_dir()?,
+            _ => data_dir()?.join("workspaces").join(name),
+        };
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            file: dir.join("tasks.json"),
+        })
+    }
+
+    // Every named workspace, starting with the default one
+    pub fn list() -> anyhow::Result<Vec<String>> {
+        let mut names = match fs::read_dir(data_dir()?.join("workspaces")) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect::<Vec<_>>(),
+            Err(_) => vec![],
+        };
+        names.sort();
+        names.insert(0, DEFAULT.to_string());
+
+        Ok(names)
+    }
+
+    fn dir(&self) -> PathBuf {
+        self.file
+            .parent()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    // The name every file that belongs to this workspace starts with
+    pub fn stem(&self) -> String {
+        self.file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn path(&self, file: TaskFile) -> PathBuf {
+        // A file named tasks.json keeps the original names for the others
+        let prefix = match self.stem().as_str() {
+            "tasks" => String::new(),
+            stem => format!("{}.", stem),
+        };
+
+        match file {
+            TaskFile::Main => self.file.clone(),
+            TaskFile::Trash => self.dir().join(format!("{}trash.json", prefix)),
+            TaskFile::Complete => self.dir().join(format!("{}complete.json", prefix)),
+            TaskFile::Statuses => self.dir().join(format!("{}statuses.json", prefix)),
+        }
+    }
+
+    // When the main file was last changed, by us or anyone else
+    pub fn modified(&self) -> Option<SystemTime> {
+        fs::metadata(&self.file)
+            .and_then(|meta| meta.modified())
+            .ok()
+    }
+
+    pub fn backups_dir(&self) -> anyhow::Result<PathBuf> {
+        let dir = self.dir().join("backups");
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+}
+
+// The directory the default workspace and config live in.
+// ~/.rtasks is kept if it already exists, otherwise the XDG data directory is used
+pub fn data_dir() -> anyhow::Result<PathBuf> {
+    let legacy = directories::UserDirs::new().map(|dirs| dirs.home_dir().join(".rtasks"));
+
+    let dir = match legacy {
+        Some(legacy) if legacy.is_dir() => legacy,
+        _ => directories::ProjectDirs::from("", "", "rtasks")
+            .map(|dirs| dirs.data_dir().to_path_buf())
+            .ok_or_else(|| anyhow!("Failed to find a data directory"))?,
+    };
+
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+// Every workspace, the open one in green
+pub fn picker_items(current: &str) -> Vec<ListItem<'static>> {
+    Workspace::list()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|name| {
+            let style = match name == current {
+                true => Style::default().fg(Color::LightGreen),
+                false => Style::default(),
+            };
+            ListItem::new(name).style(style)
+        })
+        .collect()
+}